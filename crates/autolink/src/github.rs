@@ -0,0 +1,614 @@
+//! Link GitHub references to issues, pull requests, commits, users and teams.
+//!
+//! - `#123` and `GH-123` link to an issue (or pull request) in the current repository
+//! - `owner/repo#123` links to an issue in another repository
+//! - `owner/repo@sha` links to a commit (with a 7 to 40 character SHA)
+//! - a bare `sha` links to a commit in the current repository
+//!   (it must contain both digits and letters, so that numbers and words such as `defaced` are not linked)
+//! - `@user` and `@org/team` link to a user or team
+//!
+//! As for autolinks, references are only matched at the start of a line,
+//! or after whitespace or one of `* _ ~ (`, and not inside links or code.
+//!
+//! ```rust
+//! use markdown_it_autolink::github::{add_with_options, GithubOptions};
+//!
+//! let md = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(md);
+//! add_with_options(md, GithubOptions {
+//!     repository: Some(String::from("rust-lang/rust")),
+//!     ..Default::default()
+//! });
+//!
+//! assert_eq!(
+//!     md.parse("Fixes #123, thanks @octocat").render(),
+//!     "<p>Fixes <a href=\"https://github.com/rust-lang/rust/issues/123\">#123</a>, \
+//!     thanks <a href=\"https://github.com/octocat\">@octocat</a></p>\n",
+//! );
+//! ```
+
+use markdown_it::{
+    common::sourcemap::SourcePos,
+    parser::{
+        core::CoreRule,
+        extset::MarkdownItExt,
+        inline::{builtin::InlineParserRule, InlineRule, InlineState, Text},
+    },
+    plugins::cmark::inline::{
+        autolink::Autolink,
+        backticks::CodeInline,
+        image::Image,
+        link::Link,
+        newline::{Hardbreak, Softbreak},
+    },
+    MarkdownIt, Node, NodeValue, Renderer,
+};
+
+use gfm_autolinks::check_prev;
+
+/// Add the GitHub references plugin to MarkdownIt.
+pub fn add(md: &mut MarkdownIt) {
+    md.ext.get_or_insert_default::<GithubOptions>();
+    add_rules(md);
+}
+
+/// Add the GitHub references plugin to MarkdownIt, with options.
+pub fn add_with_options(md: &mut MarkdownIt, options: GithubOptions) {
+    md.ext.insert(options);
+    add_rules(md);
+}
+
+fn add_rules(md: &mut MarkdownIt) {
+    md.inline.add_rule::<IssueScanner>();
+    md.inline.add_rule::<PrefixedIssueScanner>();
+    md.inline.add_rule::<CommitScanner>();
+    md.inline.add_rule::<MentionScanner>();
+    md.add_rule::<BareCommitRule>().after::<InlineParserRule>();
+}
+
+#[derive(Debug, Clone)]
+/// Options for the GitHub references plugin.
+///
+/// The URL templates may contain the placeholders
+/// `{owner}`, `{repo}`, `{number}`, `{sha}`, `{user}`, `{org}` and `{team}`.
+pub struct GithubOptions {
+    /// The repository (`owner/repo`) that `#123`, `GH-123` and bare SHAs refer to;
+    /// if `None`, these references are not linked.
+    pub repository: Option<String>,
+    /// The URL of an issue or pull request.
+    pub issue_url: String,
+    /// The URL of a commit.
+    pub commit_url: String,
+    /// The URL of a user.
+    pub user_url: String,
+    /// The URL of a team.
+    pub team_url: String,
+}
+impl MarkdownItExt for GithubOptions {}
+
+impl Default for GithubOptions {
+    fn default() -> Self {
+        Self {
+            repository: None,
+            issue_url: String::from("https://github.com/{owner}/{repo}/issues/{number}"),
+            commit_url: String::from("https://github.com/{owner}/{repo}/commit/{sha}"),
+            user_url: String::from("https://github.com/{user}"),
+            team_url: String::from("https://github.com/orgs/{org}/teams/{team}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A reference to a GitHub resource.
+pub enum Reference {
+    Issue {
+        owner: String,
+        repo: String,
+        number: String,
+    },
+    Commit {
+        owner: String,
+        repo: String,
+        sha: String,
+    },
+    User {
+        user: String,
+    },
+    Team {
+        org: String,
+        team: String,
+    },
+}
+
+impl Reference {
+    /// Create the URL of the reference, from the templates in the options.
+    pub fn url(&self, options: &GithubOptions) -> String {
+        match self {
+            Reference::Issue {
+                owner,
+                repo,
+                number,
+            } => fill(
+                &options.issue_url,
+                &[("owner", owner), ("repo", repo), ("number", number)],
+            ),
+            Reference::Commit { owner, repo, sha } => fill(
+                &options.commit_url,
+                &[("owner", owner), ("repo", repo), ("sha", sha)],
+            ),
+            Reference::User { user } => fill(&options.user_url, &[("user", user)]),
+            Reference::Team { org, team } => {
+                fill(&options.team_url, &[("org", org), ("team", team)])
+            }
+        }
+    }
+}
+
+/// Replace the `{name}` placeholders in a template.
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut url = template.to_string();
+    for (name, value) in values {
+        url = url.replace(&format!("{{{}}}", name), value);
+    }
+    url
+}
+
+#[derive(Debug)]
+/// AST node for a GitHub reference.
+pub struct GithubReference {
+    pub reference: Reference,
+    pub url: String,
+}
+
+impl NodeValue for GithubReference {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let mut attrs = node.attrs.clone();
+        attrs.push(("href", self.url.clone()));
+        fmt.open("a", &attrs);
+        fmt.contents(&node.children);
+        fmt.close("a");
+    }
+}
+
+/// Scanner for `#123` and `owner/repo#123`.
+pub struct IssueScanner;
+impl InlineRule for IssueScanner {
+    const MARKER: char = '#';
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        if state.link_level > 0 {
+            return None;
+        }
+        // every inline rule is run at every terminator character, not just its marker
+        if !state.src[state.pos..state.pos_max].starts_with('#') {
+            return None;
+        }
+        let options = state.md.ext.get::<GithubOptions>()?;
+        let number = match_number(&state.src[state.pos + 1..state.pos_max])?.to_string();
+        let total_len = 1 + number.len();
+
+        if let Some((owner, repo)) = back_scan_repository(state.trailing_text_get()) {
+            let bscan_len = owner.len() + 1 + repo.len();
+            if check_preceding(state, bscan_len).is_some() {
+                let reference = Reference::Issue {
+                    owner,
+                    repo,
+                    number,
+                };
+                return create_reference(state, bscan_len, bscan_len + total_len, reference);
+            }
+        }
+
+        check_preceding(state, 0)?;
+        let (owner, repo) = options.repository.as_deref()?.split_once('/')?;
+        let reference = Reference::Issue {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number,
+        };
+        create_reference(state, 0, total_len, reference)
+    }
+}
+
+/// Scanner for `GH-123`.
+pub struct PrefixedIssueScanner;
+impl InlineRule for PrefixedIssueScanner {
+    const MARKER: char = 'G';
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        if state.link_level > 0 {
+            return None;
+        }
+        let options = state.md.ext.get::<GithubOptions>()?;
+        let remaining = state.src[state.pos..state.pos_max].strip_prefix("GH-")?;
+        let number = match_number(remaining)?.to_string();
+        check_preceding(state, 0)?;
+        let (owner, repo) = options.repository.as_deref()?.split_once('/')?;
+        let total_len = 3 + number.len();
+        let reference = Reference::Issue {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            number,
+        };
+        create_reference(state, 0, total_len, reference)
+    }
+}
+
+/// Scanner for `owner/repo@sha`.
+pub struct CommitScanner;
+impl InlineRule for CommitScanner {
+    const MARKER: char = '@';
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        if state.link_level > 0 {
+            return None;
+        }
+        if !state.src[state.pos..state.pos_max].starts_with('@') {
+            return None;
+        }
+        let remaining = &state.src[state.pos + 1..state.pos_max];
+        let sha_len = remaining
+            .chars()
+            .take_while(|c| matches!(c, '0'..='9' | 'a'..='f'))
+            .count();
+        if !(7..=40).contains(&sha_len) || !is_end(&remaining[sha_len..]) {
+            return None;
+        }
+        let sha = remaining[..sha_len].to_string();
+        let (owner, repo) = back_scan_repository(state.trailing_text_get())?;
+        let bscan_len = owner.len() + 1 + repo.len();
+        check_preceding(state, bscan_len)?;
+        let reference = Reference::Commit { owner, repo, sha };
+        create_reference(state, bscan_len, bscan_len + 1 + sha_len, reference)
+    }
+}
+
+/// Scanner for `@user` and `@org/team`.
+pub struct MentionScanner;
+impl InlineRule for MentionScanner {
+    const MARKER: char = '@';
+    fn run(state: &mut InlineState) -> Option<(Node, usize)> {
+        if state.link_level > 0 {
+            return None;
+        }
+        if !state.src[state.pos..state.pos_max].starts_with('@') {
+            return None;
+        }
+        check_preceding(state, 0)?;
+        let remaining = &state.src[state.pos + 1..state.pos_max];
+        // usernames are alphanumeric or single hyphens,
+        // and cannot start or end with a hyphen
+        let user_len = remaining
+            .chars()
+            .take(39)
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .count();
+        let user = &remaining[..user_len];
+        if user_len == 0 || user.starts_with('-') || user.ends_with('-') || user.contains("--") {
+            return None;
+        }
+        let user = user.to_string();
+        let rest = &remaining[user_len..];
+
+        if let Some(team) = rest.strip_prefix('/') {
+            let team_len = team
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+                .count();
+            if team_len > 0 && is_end(&team[team_len..]) {
+                let reference = Reference::Team {
+                    org: user,
+                    team: team[..team_len].to_string(),
+                };
+                return create_reference(state, 0, 1 + user_len + 1 + team_len, reference);
+            }
+        }
+        if !is_end(rest) || rest.starts_with('/') {
+            return None;
+        }
+        create_reference(state, 0, 1 + user_len, Reference::User { user })
+    }
+}
+
+/// Core rule for bare commit SHAs, such as `a5c3785`, in the current repository.
+///
+/// Unlike the other references, SHAs do not start with a marker character,
+/// so they are found in the text nodes once the inline content has been parsed.
+pub struct BareCommitRule;
+impl CoreRule for BareCommitRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let Some(options) = md.ext.get::<GithubOptions>() else {
+            return;
+        };
+        let Some((owner, repo)) = options
+            .repository
+            .as_deref()
+            .and_then(|r| r.split_once('/'))
+        else {
+            return;
+        };
+        link_commits(root, options, owner, repo);
+    }
+}
+
+/// Replace the bare SHAs in the text nodes of a node (and its descendants) with references,
+/// except inside links, images and code.
+fn link_commits(node: &mut Node, options: &GithubOptions, owner: &str, repo: &str) {
+    if node.is::<Link>()
+        || node.is::<Autolink>()
+        || node.is::<Image>()
+        || node.is::<CodeInline>()
+        || node.is::<GithubReference>()
+    {
+        return;
+    }
+    let mut i = 0;
+    while i < node.children.len() {
+        if !node.children[i].is::<Text>() {
+            link_commits(&mut node.children[i], options, owner, repo);
+            i += 1;
+            continue;
+        }
+        // text at the start of a node (or line) may start with a SHA
+        let start_boundary = i == 0
+            || node.children[i - 1].is::<Softbreak>()
+            || node.children[i - 1].is::<Hardbreak>();
+        match split_commits(&node.children[i], start_boundary, options, owner, repo) {
+            Some(nodes) => {
+                let count = nodes.len();
+                node.children.splice(i..i + 1, nodes);
+                i += count;
+            }
+            None => i += 1,
+        }
+    }
+}
+
+/// Find the bare SHAs in a text node, and return the nodes to replace it with
+/// (or `None` if it contains none).
+fn split_commits(
+    node: &Node,
+    start_boundary: bool,
+    options: &GithubOptions,
+    owner: &str,
+    repo: &str,
+) -> Option<Vec<Node>> {
+    let content = node.cast::<Text>()?.content.as_str();
+    // positions can only be mapped if the text is unchanged from the source
+    let offset = node.srcmap.and_then(|s| {
+        let (start, end) = s.get_byte_offsets();
+        (end - start == content.len()).then_some(start)
+    });
+    let text_node = |start: usize, end: usize| {
+        let mut text = Node::new(Text {
+            content: content[start..end].to_string(),
+        });
+        text.srcmap = offset.map(|o| SourcePos::new(o + start, o + end));
+        text
+    };
+
+    let mut nodes = vec![];
+    let mut pending = 0;
+    let mut pos = 0;
+    while pos < content.len() {
+        let len = content[pos..]
+            .chars()
+            .take_while(|c| matches!(c, '0'..='9' | 'a'..='f'))
+            .count();
+        if len == 0 {
+            pos += content[pos..].chars().next().map_or(1, char::len_utf8);
+            continue;
+        }
+        let end = pos + len;
+        let sha = &content[pos..end];
+        let preceded = match content[..pos].chars().next_back() {
+            Some(prev) => check_prev(prev),
+            None => start_boundary,
+        };
+        if preceded
+            && (7..=40).contains(&len)
+            && is_end(&content[end..])
+            && sha.chars().any(|c| c.is_ascii_digit())
+            && sha.chars().any(|c| c.is_ascii_lowercase())
+        {
+            if pending < pos {
+                nodes.push(text_node(pending, pos));
+            }
+            let reference = Reference::Commit {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                sha: sha.to_string(),
+            };
+            let url = reference.url(options);
+            let mut link = Node::new(GithubReference { reference, url });
+            link.srcmap = offset.map(|o| SourcePos::new(o + pos, o + end));
+            link.children.push(text_node(pos, end));
+            nodes.push(link);
+            pending = end;
+        }
+        pos = end;
+    }
+    if pending == 0 {
+        return None;
+    }
+    if pending < content.len() {
+        nodes.push(text_node(pending, content.len()));
+    }
+    Some(nodes)
+}
+
+/// Match the digits of an issue number, at the start of the text.
+fn match_number(text: &str) -> Option<&str> {
+    let len = text.chars().take_while(|c| c.is_ascii_digit()).count();
+    if len == 0 || !is_end(&text[len..]) {
+        return None;
+    }
+    Some(&text[..len])
+}
+
+/// Check that a reference is not followed by a word character.
+fn is_end(rest: &str) -> bool {
+    !rest
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '@')
+}
+
+/// Back-scan the `owner/repo` at the end of the preceding text.
+fn back_scan_repository(trailing: &str) -> Option<(String, String)> {
+    let repo_len = trailing
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        .count();
+    let before = trailing[..trailing.len() - repo_len].strip_suffix('/')?;
+    let owner_len = before
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .count();
+    if repo_len == 0 || owner_len == 0 {
+        return None;
+    }
+    Some((
+        before[before.len() - owner_len..].to_string(),
+        trailing[trailing.len() - repo_len..].to_string(),
+    ))
+}
+
+/// Check if the preceding syntax allows a reference.
+fn check_preceding(state: &InlineState, bscan_len: usize) -> Option<()> {
+    match state.src[..state.pos - bscan_len].chars().next_back() {
+        Some(prev) if !check_prev(prev) => None,
+        _ => Some(()),
+    }
+}
+
+/// Create the reference node, removing any parsed trailing text.
+fn create_reference(
+    state: &mut InlineState,
+    bscan_len: usize,
+    total_len: usize,
+    reference: Reference,
+) -> Option<(Node, usize)> {
+    let url = reference.url(state.md.ext.get::<GithubOptions>()?);
+    state.trailing_text_pop(bscan_len);
+    state.pos -= bscan_len;
+
+    let mut text = Node::new(Text {
+        content: state.src[state.pos..state.pos + total_len].to_string(),
+    });
+    text.srcmap = state.get_map(state.pos, state.pos + total_len);
+
+    let mut node = Node::new(GithubReference { reference, url });
+    node.children.push(text);
+
+    Some((node, total_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(
+        "#123",
+        "<p><a href=\"https://github.com/o/r/issues/123\">#123</a></p>\n"
+    )]
+    #[case("(#1) GH-22.", "<p>(<a href=\"https://github.com/o/r/issues/1\">#1</a>) <a href=\"https://github.com/o/r/issues/22\">GH-22</a>.</p>\n")]
+    #[case("see rust-lang/rust#42", "<p>see <a href=\"https://github.com/rust-lang/rust/issues/42\">rust-lang/rust#42</a></p>\n")]
+    #[case(
+        "a/b@1234abc, a/b@abc",
+        "<p><a href=\"https://github.com/a/b/commit/1234abc\">a/b@1234abc</a>, a/b@abc</p>\n"
+    )]
+    #[case("@octocat and @github/docs-team", "<p><a href=\"https://github.com/octocat\">@octocat</a> and <a href=\"https://github.com/orgs/github/teams/docs-team\">@github/docs-team</a></p>\n")]
+    #[case(
+        "foo#1 C#2 bob@example.com GH-1a #1_",
+        "<p>foo#1 C#2 bob@example.com GH-1a #1_</p>\n"
+    )]
+    #[case(
+        "Go home now, a/b#abcdef1 GH-x",
+        "<p>Go home now, a/b#abcdef1 GH-x</p>\n"
+    )]
+    #[case(
+        "@foo--bar @foo- @a-b",
+        "<p>@foo--bar @foo- <a href=\"https://github.com/a-b\">@a-b</a></p>\n"
+    )]
+    #[case(
+        "@-user @user/ [#1](/x) `#1`",
+        "<p>@-user @user/ <a href=\"/x\">#1</a> <code>#1</code></p>\n"
+    )]
+    fn test_references(#[case] input: &str, #[case] expected: &str) {
+        let md = &mut markdown_it::MarkdownIt::new();
+        markdown_it::plugins::cmark::add(md);
+        add_with_options(
+            md,
+            GithubOptions {
+                repository: Some(String::from("o/r")),
+                ..Default::default()
+            },
+        );
+        assert_eq!(md.parse(input).render(), expected);
+    }
+
+    #[rstest]
+    #[case(
+        "fixed in a5c3785.",
+        "<p>fixed in <a href=\"https://github.com/o/r/commit/a5c3785\">a5c3785</a>.</p>\n"
+    )]
+    #[case(
+        "(e83c5163316f89bfbde7d9ab23ca2e25604af290)",
+        "<p>(<a href=\"https://github.com/o/r/commit/e83c5163316f89bfbde7d9ab23ca2e25604af290\">e83c5163316f89bfbde7d9ab23ca2e25604af290</a>)</p>\n"
+    )]
+    #[case(
+        "a5c378 a5c3785",
+        "<p>a5c378 <a href=\"https://github.com/o/r/commit/a5c3785\">a5c3785</a></p>\n"
+    )]
+    #[case(
+        "defaced 1234567 xa5c3785 a5c3785x a5c3785_",
+        "<p>defaced 1234567 xa5c3785 a5c3785x a5c3785_</p>\n"
+    )]
+    #[case(
+        "e83c5163316f89bfbde7d9ab23ca2e25604af2901",
+        "<p>e83c5163316f89bfbde7d9ab23ca2e25604af2901</p>\n"
+    )]
+    #[case(
+        "[a5c3785](/x) `a5c3785` *a5c3785*",
+        "<p><a href=\"/x\">a5c3785</a> <code>a5c3785</code> <em><a href=\"https://github.com/o/r/commit/a5c3785\">a5c3785</a></em></p>\n"
+    )]
+    fn test_bare_commits(#[case] input: &str, #[case] expected: &str) {
+        let md = &mut markdown_it::MarkdownIt::new();
+        markdown_it::plugins::cmark::add(md);
+        add_with_options(
+            md,
+            GithubOptions {
+                repository: Some(String::from("o/r")),
+                ..Default::default()
+            },
+        );
+        assert_eq!(md.parse(input).render(), expected);
+
+        // without a repository, bare SHAs are not linked
+        let md = &mut markdown_it::MarkdownIt::new();
+        markdown_it::plugins::cmark::add(md);
+        add(md);
+        assert!(!md.parse("a5c3785").render().contains("<a"));
+    }
+
+    #[test]
+    fn test_templates() {
+        let md = &mut markdown_it::MarkdownIt::new();
+        markdown_it::plugins::cmark::add(md);
+        add_with_options(
+            md,
+            GithubOptions {
+                issue_url: String::from("https://git.example.com/{owner}/{repo}/-/issues/{number}"),
+                user_url: String::from("/users/{user}"),
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            md.parse("#1 a/b#2 @me").render(),
+            "<p>#1 <a href=\"https://git.example.com/a/b/-/issues/2\">a/b#2</a> \
+            <a href=\"/users/me\">@me</a></p>\n"
+        );
+    }
+}
@@ -11,20 +11,54 @@
 //! );
 //! ```
 
+pub mod github;
+
 use gfm_autolinks::{
     check_prev,
     email::{match_any_email, Protocol},
-    url::match_http,
+    url::{match_http, match_scheme},
     www::match_www,
 };
 use markdown_it::{
-    parser::inline::{InlineRule, InlineState, TextSpecial},
+    parser::{
+        extset::MarkdownItExt,
+        inline::{InlineRule, InlineState, TextSpecial},
+    },
     plugins::cmark::inline::autolink::Autolink,
     MarkdownIt, Node,
 };
 
 /// Add the GFM autolink extension plugin to MarkdownIt.
 pub fn add(md: &mut MarkdownIt) {
+    md.ext.get_or_insert_default::<AutolinkOptions>();
+    add_rules(md);
+}
+
+/// Add the GFM autolink extension plugin to MarkdownIt, with options.
+///
+/// ```rust
+/// use markdown_it_autolink::{add_with_options, AutolinkOptions, Scheme};
+///
+/// let md = &mut markdown_it::MarkdownIt::new();
+/// markdown_it::plugins::cmark::add(md);
+/// add_with_options(md, AutolinkOptions {
+///     schemes: vec![Scheme::url("jira"), Scheme::email("sip")],
+///     www: false,
+///     ..Default::default()
+/// });
+///
+/// assert_eq!(
+///     md.parse("jira:PROJ-1, sip:bob@example.com or www.example.com").render(),
+///     "<p><a href=\"jira:PROJ-1\">jira:PROJ-1</a>, \
+///     <a href=\"sip:bob@example.com\">sip:bob@example.com</a> or www.example.com</p>\n",
+/// );
+/// ```
+pub fn add_with_options(md: &mut MarkdownIt, options: AutolinkOptions) {
+    md.ext.insert(options);
+    add_rules(md);
+}
+
+fn add_rules(md: &mut MarkdownIt) {
     // TODO should these rules be before/after something?
     md.inline.add_rule::<WwwScanner>();
     md.inline.add_rule::<ProtocolScanner>();
@@ -33,6 +67,63 @@ pub fn add(md: &mut MarkdownIt) {
     md.inline.add_rule::<BareEmailScanner>();
 }
 
+#[derive(Debug, Clone)]
+/// Options for the autolink plugin.
+pub struct AutolinkOptions {
+    /// Schemes to match, in addition to `http`, `https`, `mailto` and `xmpp`
+    /// (the first matching scheme is used).
+    pub schemes: Vec<Scheme>,
+    /// Whether to match bare `www.` links.
+    pub www: bool,
+    /// Whether to match bare email addresses (without `mailto:`).
+    pub bare_email: bool,
+}
+impl MarkdownItExt for AutolinkOptions {}
+
+impl Default for AutolinkOptions {
+    fn default() -> Self {
+        Self {
+            schemes: vec![],
+            www: true,
+            bare_email: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A URI scheme to match, such as `ftp` or `jira` (without the `:`).
+pub struct Scheme {
+    pub name: String,
+    pub kind: SchemeKind,
+}
+
+impl Scheme {
+    /// A scheme followed by any non-whitespace text, e.g. `ftp://example.com/file` or `jira:PROJ-123`.
+    pub fn url(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: SchemeKind::Url,
+        }
+    }
+
+    /// A scheme followed by an email address, e.g. `sip:bob@example.com` (as for `mailto:`).
+    pub fn email(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            kind: SchemeKind::Email,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// How the text after a scheme is matched.
+pub enum SchemeKind {
+    /// Any non-whitespace text, excluding trailing punctuation.
+    Url,
+    /// An email address.
+    Email,
+}
+
 /// Scanner for bare `www.` URLs.
 pub struct WwwScanner;
 impl InlineRule for WwwScanner {
@@ -41,6 +132,14 @@ impl InlineRule for WwwScanner {
         if state.link_level > 0 {
             return None;
         }
+        if state
+            .md
+            .ext
+            .get::<AutolinkOptions>()
+            .is_some_and(|o| !o.www)
+        {
+            return None;
+        }
         if !state.src[state.pos..state.pos_max].starts_with("www.") {
             return None;
         }
@@ -54,7 +153,7 @@ impl InlineRule for WwwScanner {
 }
 
 /// Scanner for URL protocols that are supported by the GFM autolink extension;
-/// `http`, `https`, `mailto`, and `xmpp`.
+/// `http`, `https`, `mailto`, and `xmpp` (plus any `AutolinkOptions::schemes`).
 /// The rule searches for `:`, back-scans to match the protocol name,
 /// then forward-spans to match the rest of the URL.
 /// Note, this is a balance between performance (only activating on `:` for all protocols)
@@ -106,10 +205,31 @@ impl InlineRule for ProtocolScanner {
             return create_autolink(state, bscan_len, total_len, full_url, None);
         }
 
+        // the options are borrowed from the parser, not the state
+        let md = state.md;
+        for scheme in &md.ext.get::<AutolinkOptions>()?.schemes {
+            if !trailing.ends_with(&scheme.name) {
+                continue;
+            }
+            let bscan_len = scheme.name.len();
+            if check_preceding(state, bscan_len).is_none() {
+                continue;
+            }
+            let contents = [scheme.name.as_bytes(), remaining.as_bytes()].concat();
+            let matched = match scheme.kind {
+                SchemeKind::Url => match_scheme(&contents, &scheme.name),
+                SchemeKind::Email => match_any_email(&contents, bscan_len + 1, Protocol::Mailto),
+            };
+            if let Some((full_url, total_len)) = matched {
+                return create_autolink(state, bscan_len, total_len, full_url, None);
+            }
+        }
+
         None
     }
 }
 
+/// Scanner for bare email addresses.
 pub struct BareEmailScanner;
 impl InlineRule for BareEmailScanner {
     const MARKER: char = '@';
@@ -118,6 +238,14 @@ impl InlineRule for BareEmailScanner {
         if state.link_level > 0 {
             return None;
         }
+        if state
+            .md
+            .ext
+            .get::<AutolinkOptions>()
+            .is_some_and(|o| !o.bare_email)
+        {
+            return None;
+        }
 
         // remaining text must start with `@` and have at least 1 more chars
         let mut chars = state.src[state.pos..state.pos_max].chars();
@@ -253,4 +381,44 @@ mod tests {
         });
         assert_eq!(pos, expected);
     }
+
+    #[rstest]
+    #[case(
+        "ftp://example.com/a.txt.",
+        "<p><a href=\"ftp://example.com/a.txt\">ftp://example.com/a.txt</a>.</p>\n"
+    )]
+    #[case(
+        "open vscode://file/a.rs:10",
+        "<p>open <a href=\"vscode://file/a.rs:10\">vscode://file/a.rs:10</a></p>\n"
+    )]
+    #[case("(jira:PROJ-1)", "<p>(<a href=\"jira:PROJ-1\">jira:PROJ-1</a>)</p>\n")]
+    #[case("xjira:PROJ-1 git://", "<p>xjira:PROJ-1 git://</p>\n")]
+    #[case(
+        "sip:bob@example.com sip:bob",
+        "<p><a href=\"sip:bob@example.com\">sip:bob@example.com</a> sip:bob</p>\n"
+    )]
+    #[case(
+        "www.example.com bob@example.com",
+        "<p>www.example.com bob@example.com</p>\n"
+    )]
+    #[case("mailto:bob@example.com https://example.com", "<p><a href=\"mailto:bob@example.com\">mailto:bob@example.com</a> <a href=\"https://example.com\">https://example.com</a></p>\n")]
+    fn test_options(#[case] input: &str, #[case] expected: &str) {
+        let md = &mut markdown_it::MarkdownIt::new();
+        markdown_it::plugins::cmark::add(md);
+        add_with_options(
+            md,
+            AutolinkOptions {
+                schemes: vec![
+                    Scheme::url("ftp"),
+                    Scheme::url("git"),
+                    Scheme::url("vscode"),
+                    Scheme::url("jira"),
+                    Scheme::email("sip"),
+                ],
+                www: false,
+                bare_email: false,
+            },
+        );
+        assert_eq!(md.parse(input).render(), expected);
+    }
 }
@@ -131,6 +131,28 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case("ftp://example.com/file.txt", "ftp", Some(("ftp://example.com/file.txt", 26)))]
+    #[case("ssh://git@host:22.", "ssh", Some(("ssh://git@host:22", 17)))]
+    #[case("vscode://file/a/b.rs:10 more", "vscode", Some(("vscode://file/a/b.rs:10", 23)))]
+    #[case("jira:PROJ-123, see", "jira", Some(("jira:PROJ-123", 13)))]
+    #[case("jira:PROJ-123", "git", None)]
+    #[case("jira", "jira", None)]
+    #[case("jira:", "jira", None)]
+    #[case("jira:.", "jira", None)]
+    #[case("git://", "git", None)]
+    #[case("irc://irc.libera.chat/#rust", "irc", Some(("irc://irc.libera.chat/#rust", 27)))]
+    fn test_match_scheme(
+        #[case] input: &str,
+        #[case] scheme: &str,
+        #[case] expected: Option<(&str, i32)>,
+    ) {
+        assert_eq!(
+            url::match_scheme(input.as_bytes(), scheme),
+            expected.map(|a| (a.0.to_string(), a.1 as usize))
+        );
+    }
+
     #[rstest]
     #[case("www.commonmark.org", 0, Some(("http://www.commonmark.org", 18)))]
     #[case(" www.commonmark.org", 0, None)]
@@ -33,3 +33,34 @@ pub fn match_http(contents: &[u8]) -> Option<(String, usize)> {
     let skip_len = url.chars().count();
     Some((url, skip_len))
 }
+
+/// Match a URL link starting with the given scheme (such as `ftp` or `jira`) followed by `:`,
+/// from the start of the string.
+/// The link extends to the next whitespace, and is correctly terminated (as for `match_http`),
+/// but the domain is not checked.
+/// Return the link and the number of chars to skip.
+pub fn match_scheme(contents: &[u8], scheme: &str) -> Option<(String, usize)> {
+    if scheme.is_empty()
+        || !contents.starts_with(scheme.as_bytes())
+        || contents.get(scheme.len()) != Some(&b':')
+    {
+        return None;
+    }
+    let prefix_len = scheme.len() + 1;
+
+    let mut link_end = prefix_len;
+    while link_end < contents.len() && !isspace(contents[link_end]) {
+        link_end += 1;
+    }
+
+    link_end = autolink_delim(contents, link_end);
+
+    // the link must contain more than the scheme (and slashes)
+    if link_end <= prefix_len || contents[prefix_len..link_end].iter().all(|&c| c == b'/') {
+        return None;
+    }
+
+    let url = str::from_utf8(&contents[..link_end]).ok()?.to_string();
+    let skip_len = url.chars().count();
+    Some((url, skip_len))
+}
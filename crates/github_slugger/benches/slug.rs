@@ -0,0 +1,48 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const HEADINGS: &[&str] = &[
+    "Installation",
+    "already-a-slug",
+    "Getting Started with the API",
+    "What's new in v2.0?",
+    "Привет non-latin 你好",
+    "😄 emoji and `code` (with punctuation)!",
+];
+
+fn bench_slug(c: &mut Criterion) {
+    c.bench_function("slug", |b| {
+        b.iter(|| {
+            for heading in HEADINGS {
+                black_box(github_slugger::slug(black_box(heading)));
+            }
+        })
+    });
+    c.bench_function("slug_cow", |b| {
+        b.iter(|| {
+            for heading in HEADINGS {
+                black_box(github_slugger::slug_cow(black_box(heading)));
+            }
+        })
+    });
+    c.bench_function("slug_into", |b| {
+        let mut buffer = String::new();
+        b.iter(|| {
+            for heading in HEADINGS {
+                buffer.clear();
+                github_slugger::slug_into(black_box(heading), &mut buffer);
+                black_box(&buffer);
+            }
+        })
+    });
+    c.bench_function("slugger", |b| {
+        b.iter(|| {
+            let mut slugger = github_slugger::Slugger::default();
+            for heading in HEADINGS {
+                black_box(slugger.slug(black_box(heading)));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_slug);
+criterion_main!(benches);
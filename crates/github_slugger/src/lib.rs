@@ -1,14 +1,28 @@
 //! Generate header slugs for GitHub Markdown.
 //! Adapted from https://github.com/Flet/github-slugger
+//!
+//! The slug algorithms of other tools are also available, see [`Algorithm`].
+
+mod transliterate;
+
+pub use transliterate::transliterate;
 
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::collections::HashSet;
+use std::{borrow::Cow, collections::HashSet};
 
 #[derive(Default, Debug)]
 pub struct Slugger {
     /// The set of slugs we've seen so far
     slugs: HashSet<String>,
+    /// The algorithm used by `slug`
+    algorithm: Algorithm,
+    /// Whether `slug` transliterates the input to ASCII first
+    ascii: bool,
+    /// Ids that are never generated
+    reserved: HashSet<String>,
+    /// The maximum length of generated slugs
+    max_length: Option<usize>,
 }
 
 // See https://github.com/rust-lang/regex/blob/master/UNICODE.md#rl12-properties
@@ -17,14 +31,47 @@ static REMOVE_PAT: &str = r"[\p{Other_Number}\p{Close_Punctuation}\p{Final_Punct
 static REMOVE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(REMOVE_PAT).unwrap());
 
 impl Slugger {
+    /// Create a slugger that uses the given algorithm.
+    pub fn new(algorithm: Algorithm) -> Self {
+        Self {
+            algorithm,
+            ..Default::default()
+        }
+    }
+
+    /// Set whether to transliterate the input to ASCII before generating the slug
+    /// (see [`transliterate`]).
+    pub fn ascii(mut self, ascii: bool) -> Self {
+        self.ascii = ascii;
+        self
+    }
+
     /// Generate a slug for the given string.
     pub fn slug(&mut self, s: &str) -> String {
-        // if we've already seen this slug, add a number to the end
-        let base = slug(s);
-        let mut result = base.clone();
+        if self.ascii {
+            self.unique(&self.algorithm.slug(&transliterate(s)))
+        } else {
+            self.unique(&self.algorithm.slug(s))
+        }
+    }
+
+    /// Set the maximum length (in characters) of generated slugs.
+    ///
+    /// Longer slugs are truncated at the last `-` that fits (or at the maximum length,
+    /// if there is none), leaving room for any suffix added to make them unique.
+    pub fn max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Make a (pre-computed) slug unique,
+    /// by adding a number to the end if we've already seen it.
+    pub fn unique(&mut self, base: &str) -> String {
+        let mut result = self.truncate(base, 0).to_string();
         let mut i = 1;
-        while self.slugs.contains(&result) {
-            result = format!("{}-{}", base, i);
+        while self.is_taken(&result) {
+            let suffix = format!("-{}", i);
+            result = format!("{}{}", self.truncate(base, suffix.len()), suffix);
             i += 1;
         }
 
@@ -32,31 +79,251 @@ impl Slugger {
         result
     }
 
-    /// Clear the set of slugs we've seen so far.
+    /// Make a (pre-computed) slug unique, by qualifying it with the slugs of its parents
+    /// (nearest first, e.g. `usage-linux` then `guide-usage-linux`),
+    /// before falling back to adding a number.
+    ///
+    /// Unlike `unique`, a duplicate slug does not depend on how many headings with the same text
+    /// came before it, only on its parents. The first heading with a given text still takes the bare
+    /// slug, so inserting such a heading earlier in the document changes the slug of a later one
+    /// (e.g. from `linux` to `install-linux`); to keep published slugs stable, register them first.
+    pub fn unique_path(&mut self, base: &str, parents: &[&str]) -> String {
+        let mut result = self.truncate(base, 0).to_string();
+        for parent in parents.iter().rev() {
+            if !self.is_taken(&result) {
+                break;
+            }
+            result = self
+                .truncate(&format!("{}-{}", parent, result), 0)
+                .to_string();
+        }
+        if self.is_taken(&result) {
+            return self.unique(base);
+        }
+        self.slugs.insert(result.clone());
+        result
+    }
+
+    /// Mark a slug as seen, without modifying it,
+    /// so that it will not be generated by later calls to `slug` or `unique`.
+    pub fn register(&mut self, slug: &str) {
+        self.slugs.insert(slug.to_string());
+    }
+
+    /// Reserve an id (such as `content` or `footer`, used by the rest of the page),
+    /// so that it will never be generated, even after `reset`.
+    pub fn reserve(&mut self, id: &str) {
+        self.reserved.insert(id.to_string());
+    }
+
+    /// Whether an id has been reserved with `reserve`.
+    pub fn is_reserved(&self, id: &str) -> bool {
+        self.reserved.contains(id)
+    }
+
+    /// Truncate a (pre-computed) slug to the maximum length, as `unique` does before adding a suffix.
+    pub fn truncated<'a>(&self, slug: &'a str) -> &'a str {
+        self.truncate(slug, 0)
+    }
+
+    fn is_taken(&self, slug: &str) -> bool {
+        self.slugs.contains(slug) || self.reserved.contains(slug)
+    }
+
+    /// Truncate a slug to the maximum length, minus `reserve` characters,
+    /// at the last `-` that fits.
+    fn truncate<'a>(&self, slug: &'a str, reserve: usize) -> &'a str {
+        let Some(max_length) = self.max_length else {
+            return slug;
+        };
+        let Some((end, _)) = slug.char_indices().nth(max_length.saturating_sub(reserve)) else {
+            return slug;
+        };
+        let prefix = &slug[..end];
+        if slug[end..].starts_with('-') {
+            return prefix;
+        }
+        match prefix.rfind('-') {
+            Some(boundary) if boundary > 0 => &prefix[..boundary],
+            _ => prefix,
+        }
+    }
+
+    /// Clear the set of slugs we've seen so far (but not the reserved ids).
     pub fn reset(&mut self) {
         self.slugs.clear();
     }
 }
 
-pub fn slug(input: &str) -> String {
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// A slug algorithm, matching the heading ids generated by a specific tool.
+///
+/// All of these tools de-duplicate slugs in the same way, by appending `-1`, `-2`, etc.
+pub enum Algorithm {
+    /// GitHub (the default), see [`slug`].
+    #[default]
+    GitHub,
+    /// GitLab: punctuation is removed, spaces are replaced with `-`,
+    /// repeated `-` are collapsed, and slugs of only digits are prefixed with `anchor-`.
+    GitLab,
+    /// Pandoc `auto_identifiers`: characters other than alphanumerics, `_`, `-` and `.` are removed,
+    /// whitespace runs are replaced with `-`, everything before the first letter is removed,
+    /// and an empty slug is replaced with `section`.
+    Pandoc,
+    /// Pandoc `gfm_auto_identifiers`: characters other than alphanumerics, marks, `_` and `-` are removed,
+    /// each whitespace character is replaced with `-`, and an empty slug is replaced with `section`.
+    PandocGfm,
+    /// mdBook: characters other than alphanumerics, `_` and `-` are removed,
+    /// each whitespace character is replaced with `-`, and only ASCII letters are lowercased.
+    MdBook,
+    /// Docusaurus, which uses the JavaScript github-slugger package, so is the same as GitHub.
+    Docusaurus,
+}
+
+static GITLAB_REMOVE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[^\p{L}\p{M}\p{Nd}\p{Pc}\- ]").unwrap());
+static GITLAB_DASHES_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"-+").unwrap());
+static PANDOC_REMOVE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[^\p{Alphabetic}\p{N}_.\-\s]").unwrap());
+static PANDOC_GFM_REMOVE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[^\p{Alphabetic}\p{N}\p{M}\p{Pc}\-\s]").unwrap());
+
+impl Algorithm {
+    /// Generate a slug for the given string, without de-duplication.
+    pub fn slug(&self, input: &str) -> String {
+        match self {
+            Algorithm::GitHub | Algorithm::Docusaurus => slug(input),
+            Algorithm::GitLab => gitlab_slug(input),
+            Algorithm::Pandoc => pandoc_slug(input, false),
+            Algorithm::PandocGfm => pandoc_slug(input, true),
+            Algorithm::MdBook => mdbook_slug(input),
+        }
+    }
+}
+
+/// Adapted from `Gitlab::Utils::Markdown#string_to_anchor`
+fn gitlab_slug(input: &str) -> String {
+    let s = input.trim().to_lowercase();
+    let s = GITLAB_REMOVE_RE.replace_all(&s, "").replace(' ', "-");
+    let s = GITLAB_DASHES_RE.replace_all(&s, "-");
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_digit()) {
+        format!("anchor-{}", s)
+    } else {
+        s.into_owned()
+    }
+}
+
+/// Adapted from `inlineListToIdentifier` and `uniqueIdent` in `Text.Pandoc.Shared`
+fn pandoc_slug(input: &str, gfm: bool) -> String {
     let s = input.to_lowercase();
+    let s = if gfm {
+        PANDOC_GFM_REMOVE_RE
+            .replace_all(&s, "")
+            .replace(char::is_whitespace, "-")
+    } else {
+        let s = PANDOC_REMOVE_RE.replace_all(&s, "");
+        let s = s.split_whitespace().collect::<Vec<_>>().join("-");
+        s.trim_start_matches(|c: char| !c.is_alphabetic())
+            .to_string()
+    };
+    if s.is_empty() {
+        String::from("section")
+    } else {
+        s
+    }
+}
+
+/// Adapted from `id_from_content` and `normalize_id` in `mdbook::utils`
+/// (given the plain text of the heading, rather than its HTML)
+fn mdbook_slug(input: &str) -> String {
+    input
+        .trim()
+        .trim_start_matches('#')
+        .trim()
+        .chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                Some(c.to_ascii_lowercase())
+            } else if c.is_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
 
-    // apply function to regex matches
-    let s = REMOVE_RE.replace_all(&s, |caps: &regex::Captures| {
-        let c = caps.get(0).unwrap().as_str();
-        if c == " " || c == "-" {
-            "-".to_string()
-        } else if c.chars().all(|a| a.is_alphabetic()) {
-            // note in "Other Symbols" this matches:
-            // ⓐⓑⓒⓓⓔⓕⓖⓗⓘⓙⓚⓛⓜⓝⓞⓟⓠⓡⓢⓣⓤⓥⓦⓧⓨⓩ
-            // ⓐⓑⓒⓓⓔⓕⓖⓗⓘⓙⓚⓛⓜⓝⓞⓟⓠⓡⓢⓣⓤⓥⓦⓧⓨⓩ
-            // 🄰🄱🄲🄳🄴🄵🄶🄷🄸🄹🄺🄻🄼🄽🄾🄿🅀🅁🅂🅃🅄🅅🅆🅇🅈🅉
-            // 🅐🅑🅒🅓🅔🅕🅖🅗🅘🅙🅚🅛🅜🅝🅞🅟🅠🅡🅢🅣🅤🅥🅦🅧🅨🅩
-            // 🅰🅱🅲🅳🅴🅵🅶🅷🅸🅹🅺🅻🅼🅽🅾🅿🆀🆁🆂🆃🆄🆅🆆🆇🆈🆉
-            c.to_string()
+/// Generate a slug for the given string, in the same way as GitHub.
+pub fn slug(input: &str) -> String {
+    slug_cow(input).into_owned()
+}
+
+/// Generate a slug for the given string, in the same way as GitHub,
+/// borrowing the input if it is already a valid slug.
+pub fn slug_cow(input: &str) -> Cow<'_, str> {
+    if input
+        .chars()
+        .all(|c| is_lowercase(c) && map_char(c) == Some(c))
+    {
+        Cow::Borrowed(input)
+    } else {
+        let mut output = String::with_capacity(input.len());
+        slug_into(input, &mut output);
+        Cow::Owned(output)
+    }
+}
+
+/// Generate a slug for the given string, in the same way as GitHub,
+/// appending it to a (reusable) buffer.
+pub fn slug_into(input: &str, output: &mut String) {
+    // the lowercase form of `Σ` depends on its context (final sigma),
+    // so that case is handled by lowercasing the whole string first
+    if input.contains('Σ') {
+        output.extend(input.to_lowercase().chars().filter_map(map_char));
+        return;
+    }
+    for c in input.chars() {
+        if c.is_ascii() {
+            if let Some(c) = map_char(c.to_ascii_lowercase()) {
+                output.push(c);
+            }
         } else {
-            "".to_string()
+            output.extend(c.to_lowercase().filter_map(map_char));
         }
-    });
-    s.replace(|c: char| c.is_whitespace(), "-")
+    }
+}
+
+/// Whether the character is unchanged by lowercasing.
+fn is_lowercase(c: char) -> bool {
+    if c.is_ascii() {
+        return !c.is_ascii_uppercase();
+    }
+    let mut lower = c.to_lowercase();
+    lower.next() == Some(c) && lower.next().is_none()
+}
+
+/// Map a (lowercase) character to its replacement in the slug, or None if it is removed.
+fn map_char(c: char) -> Option<char> {
+    if c.is_ascii() {
+        // of the ASCII characters, only letters, digits and `_` are not matched by `REMOVE_PAT`
+        return match c {
+            'a'..='z' | '0'..='9' | '_' => Some(c),
+            ' ' | '-' => Some('-'),
+            _ => None,
+        };
+    }
+    let mut buffer = [0; 4];
+    if REMOVE_RE.is_match(c.encode_utf8(&mut buffer)) {
+        // note in "Other Symbols" this matches:
+        // ⓐⓑⓒⓓⓔⓕⓖⓗⓘⓙⓚⓛⓜⓝⓞⓟⓠⓡⓢⓣⓤⓥⓦⓧⓨⓩ
+        // ⓐⓑⓒⓓⓔⓕⓖⓗⓘⓙⓚⓛⓜⓝⓞⓟⓠⓡⓢⓣⓤⓥⓦⓧⓨⓩ
+        // 🄰🄱🄲🄳🄴🄵🄶🄷🄸🄹🄺🄻🄼🄽🄾🄿🅀🅁🅂🅃🅄🅅🅆🅇🅈🅉
+        // 🅐🅑🅒🅓🅔🅕🅖🅗🅘🅙🅚🅛🅜🅝🅞🅟🅠🅡🅢🅣🅤🅥🅦🅧🅨🅩
+        // 🅰🅱🅲🅳🅴🅵🅶🅷🅸🅹🅺🅻🅼🅽🅾🅿🆀🆁🆂🆃🆄🆅🆆🆇🆈🆉
+        c.is_alphabetic().then_some(c)
+    } else if c.is_whitespace() {
+        Some('-')
+    } else {
+        Some(c)
+    }
 }
@@ -0,0 +1,270 @@
+//! Transliterate text to ASCII, for slugs that must not contain non-ASCII characters.
+
+/// Transliterate text to ASCII, before generating a slug.
+///
+/// Latin letters with diacritics are replaced with their base letters (`é` → `e`, `ß` → `ss`),
+/// and Cyrillic and Greek letters are romanised (`Привет` → `Privet`, `Ελλάδα` → `Ellada`).
+/// Each run of other non-ASCII letters and numbers (such as CJK text) is replaced with
+/// a stable hash of the run (`u` followed by 8 hex digits), so it is still distinguishable.
+/// Other characters are kept, to be handled by the slug algorithm.
+pub fn transliterate(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    // the current run of letters and numbers with no mapping
+    let mut run = String::new();
+    for c in input.chars() {
+        if c.is_ascii() {
+            flush(&mut run, &mut output, c.is_ascii_alphanumeric());
+            output.push(c);
+            continue;
+        }
+        if is_combining(c) {
+            continue;
+        }
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        if let Some(mapped) = lookup(lower) {
+            flush(&mut run, &mut output, !mapped.is_empty());
+            let mut chars = mapped.chars();
+            if let Some(first) = chars.next() {
+                if c.is_uppercase() {
+                    output.push(first.to_ascii_uppercase());
+                } else {
+                    output.push(first);
+                }
+                output.extend(chars);
+            }
+        } else if c.is_alphanumeric() {
+            run.push(c);
+        } else {
+            flush(&mut run, &mut output, false);
+            output.push(c);
+        }
+    }
+    flush(&mut run, &mut output, false);
+    output
+}
+
+/// Replace a run of unmapped characters with its hash,
+/// separated by spaces from any adjacent letters or numbers.
+fn flush(run: &mut String, output: &mut String, alphanumeric_next: bool) {
+    if run.is_empty() {
+        return;
+    }
+    if output.chars().last().is_some_and(|c| c.is_alphanumeric()) {
+        output.push(' ');
+    }
+    output.push_str(&format!("u{:08x}", fnv1a(run.as_bytes())));
+    if alphanumeric_next {
+        output.push(' ');
+    }
+    run.clear();
+}
+
+/// The 32-bit FNV-1a hash, which is stable across versions and platforms.
+fn fnv1a(bytes: &[u8]) -> u32 {
+    let mut hash: u32 = 0x811c9dc5;
+    for byte in bytes {
+        hash ^= *byte as u32;
+        hash = hash.wrapping_mul(0x01000193);
+    }
+    hash
+}
+
+/// Whether the character is a combining diacritical mark, which is removed.
+fn is_combining(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+    )
+}
+
+fn lookup(c: char) -> Option<&'static str> {
+    TABLE
+        .binary_search_by_key(&c, |(k, _)| *k)
+        .ok()
+        .map(|i| TABLE[i].1)
+}
+
+/// Lowercase letters and their ASCII transliteration, sorted by letter.
+static TABLE: &[(char, &str)] = &[
+    ('ß', "ss"),
+    ('à', "a"),
+    ('á', "a"),
+    ('â', "a"),
+    ('ã', "a"),
+    ('ä', "a"),
+    ('å', "a"),
+    ('æ', "ae"),
+    ('ç', "c"),
+    ('è', "e"),
+    ('é', "e"),
+    ('ê', "e"),
+    ('ë', "e"),
+    ('ì', "i"),
+    ('í', "i"),
+    ('î', "i"),
+    ('ï', "i"),
+    ('ð', "d"),
+    ('ñ', "n"),
+    ('ò', "o"),
+    ('ó', "o"),
+    ('ô', "o"),
+    ('õ', "o"),
+    ('ö', "o"),
+    ('ø', "o"),
+    ('ù', "u"),
+    ('ú', "u"),
+    ('û', "u"),
+    ('ü', "u"),
+    ('ý', "y"),
+    ('þ', "th"),
+    ('ÿ', "y"),
+    ('ā', "a"),
+    ('ă', "a"),
+    ('ą', "a"),
+    ('ć', "c"),
+    ('ĉ', "c"),
+    ('ċ', "c"),
+    ('č', "c"),
+    ('ď', "d"),
+    ('đ', "d"),
+    ('ē', "e"),
+    ('ĕ', "e"),
+    ('ė', "e"),
+    ('ę', "e"),
+    ('ě', "e"),
+    ('ĝ', "g"),
+    ('ğ', "g"),
+    ('ġ', "g"),
+    ('ģ', "g"),
+    ('ĥ', "h"),
+    ('ħ', "h"),
+    ('ĩ', "i"),
+    ('ī', "i"),
+    ('ĭ', "i"),
+    ('į', "i"),
+    ('ı', "i"),
+    ('ĳ', "ij"),
+    ('ĵ', "j"),
+    ('ķ', "k"),
+    ('ĺ', "l"),
+    ('ļ', "l"),
+    ('ľ', "l"),
+    ('ŀ', "l"),
+    ('ł', "l"),
+    ('ń', "n"),
+    ('ņ', "n"),
+    ('ň', "n"),
+    ('ŉ', "n"),
+    ('ŋ', "ng"),
+    ('ō', "o"),
+    ('ŏ', "o"),
+    ('ő', "o"),
+    ('œ', "oe"),
+    ('ŕ', "r"),
+    ('ŗ', "r"),
+    ('ř', "r"),
+    ('ś', "s"),
+    ('ŝ', "s"),
+    ('ş', "s"),
+    ('š', "s"),
+    ('ţ', "t"),
+    ('ť', "t"),
+    ('ŧ', "t"),
+    ('ũ', "u"),
+    ('ū', "u"),
+    ('ŭ', "u"),
+    ('ů', "u"),
+    ('ű', "u"),
+    ('ų', "u"),
+    ('ŵ', "w"),
+    ('ŷ', "y"),
+    ('ź', "z"),
+    ('ż', "z"),
+    ('ž', "z"),
+    ('ș', "s"),
+    ('ț', "t"),
+    ('ΐ', "i"),
+    ('ά', "a"),
+    ('έ', "e"),
+    ('ή', "i"),
+    ('ί', "i"),
+    ('ΰ', "y"),
+    ('α', "a"),
+    ('β', "v"),
+    ('γ', "g"),
+    ('δ', "d"),
+    ('ε', "e"),
+    ('ζ', "z"),
+    ('η', "i"),
+    ('θ', "th"),
+    ('ι', "i"),
+    ('κ', "k"),
+    ('λ', "l"),
+    ('μ', "m"),
+    ('ν', "n"),
+    ('ξ', "x"),
+    ('ο', "o"),
+    ('π', "p"),
+    ('ρ', "r"),
+    ('ς', "s"),
+    ('σ', "s"),
+    ('τ', "t"),
+    ('υ', "y"),
+    ('φ', "f"),
+    ('χ', "ch"),
+    ('ψ', "ps"),
+    ('ω', "o"),
+    ('ϊ', "i"),
+    ('ϋ', "y"),
+    ('ό', "o"),
+    ('ύ', "y"),
+    ('ώ', "o"),
+    ('а', "a"),
+    ('б', "b"),
+    ('в', "v"),
+    ('г', "g"),
+    ('д', "d"),
+    ('е', "e"),
+    ('ж', "zh"),
+    ('з', "z"),
+    ('и', "i"),
+    ('й', "y"),
+    ('к', "k"),
+    ('л', "l"),
+    ('м', "m"),
+    ('н', "n"),
+    ('о', "o"),
+    ('п', "p"),
+    ('р', "r"),
+    ('с', "s"),
+    ('т', "t"),
+    ('у', "u"),
+    ('ф', "f"),
+    ('х', "kh"),
+    ('ц', "ts"),
+    ('ч', "ch"),
+    ('ш', "sh"),
+    ('щ', "shch"),
+    ('ъ', ""),
+    ('ы', "y"),
+    ('ь', ""),
+    ('э', "e"),
+    ('ю', "yu"),
+    ('я', "ya"),
+    ('ё', "yo"),
+    ('ђ', "dj"),
+    ('є', "ye"),
+    ('і', "i"),
+    ('ї', "yi"),
+    ('ј', "j"),
+    ('љ', "lj"),
+    ('њ', "nj"),
+    ('ћ', "c"),
+    ('ў', "u"),
+    ('џ', "dz"),
+    ('ґ', "g"),
+];
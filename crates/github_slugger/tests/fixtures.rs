@@ -1,4 +1,5 @@
 // add test
+use github_slugger::Algorithm;
 use serde::{Deserialize, Serialize};
 use std::{env, fs::read_to_string, path::PathBuf};
 
@@ -9,16 +10,20 @@ struct Case {
     expected: String,
 }
 
-#[test]
-fn test_cases() {
-    // read test cases from tests/cases.json
-    // TODO maybe could use https://github.com/commure/datatest instead
+fn read_cases(path: &str) -> Vec<Case> {
     let cases_path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").expect(
         "#[fixture] requires CARGO_MANIFEST_DIR because it's relative to cargo manifest directory",
     ))
-    .join("tests/fixtures.json");
+    .join(path);
     let cases_text = read_to_string(&cases_path).unwrap();
-    let cases: Vec<Case> = serde_json::from_str::<Vec<Case>>(&cases_text).unwrap();
+    serde_json::from_str::<Vec<Case>>(&cases_text).unwrap()
+}
+
+#[test]
+fn test_cases() {
+    // read test cases from tests/cases.json
+    // TODO maybe could use https://github.com/commure/datatest instead
+    let cases = read_cases("tests/fixtures.json");
 
     // setup slugger
     let mut slugger = github_slugger::Slugger::default();
@@ -29,3 +34,145 @@ fn test_cases() {
         assert_eq!(actual, case.expected, "case #{}: {}", num, case.name);
     }
 }
+
+#[test]
+fn test_algorithms() {
+    for (path, algorithm) in [
+        ("tests/algorithms/gitlab.json", Algorithm::GitLab),
+        ("tests/algorithms/pandoc.json", Algorithm::Pandoc),
+        ("tests/algorithms/pandoc_gfm.json", Algorithm::PandocGfm),
+        ("tests/algorithms/mdbook.json", Algorithm::MdBook),
+        ("tests/algorithms/docusaurus.json", Algorithm::Docusaurus),
+    ] {
+        let mut slugger = github_slugger::Slugger::new(algorithm);
+        for (num, case) in read_cases(path).iter().enumerate() {
+            let actual = slugger.slug(&case.input);
+            assert_eq!(
+                actual, case.expected,
+                "{} case #{}: {}",
+                path, num, case.name
+            );
+        }
+    }
+}
+
+#[test]
+fn test_unique_path() {
+    let mut slugger = github_slugger::Slugger::default();
+    assert_eq!(slugger.unique_path("linux", &["install"]), "linux");
+    assert_eq!(slugger.unique_path("linux", &["usage"]), "usage-linux");
+    assert_eq!(
+        slugger.unique_path("linux", &["guide", "usage"]),
+        "guide-usage-linux"
+    );
+    assert_eq!(slugger.unique_path("linux", &["guide", "usage"]), "linux-1");
+    assert_eq!(slugger.unique_path("linux", &[]), "linux-2");
+}
+
+#[test]
+fn test_transliterate() {
+    let mut slugger = github_slugger::Slugger::default().ascii(true);
+    assert_eq!(slugger.slug("Café Ñandú"), "cafe-nandu");
+    assert_eq!(slugger.slug("Straße Œuvre"), "strasse-oeuvre");
+    assert_eq!(slugger.slug("Привет, мир"), "privet-mir");
+    assert_eq!(slugger.slug("Щука и ёж"), "shchuka-i-yozh");
+    assert_eq!(slugger.slug("Ελληνικά"), "ellinika");
+    assert_eq!(slugger.slug("e\u{301}te\u{301}"), "ete");
+
+    // runs of other letters are replaced with a stable hash
+    let hashed = slugger.slug("你好 world");
+    assert!(hashed.is_ascii(), "{}", hashed);
+    assert_eq!(hashed.len(), "u00000000-world".len(), "{}", hashed);
+    assert!(hashed.starts_with('u') && hashed.ends_with("-world"));
+    assert_eq!(
+        github_slugger::transliterate("你好"),
+        github_slugger::transliterate("你好")
+    );
+    assert_ne!(
+        github_slugger::transliterate("你好"),
+        github_slugger::transliterate("再见")
+    );
+    assert_eq!(
+        github_slugger::transliterate("a你好b").split(' ').count(),
+        3
+    );
+}
+
+#[test]
+fn test_reserved() {
+    let mut slugger = github_slugger::Slugger::default();
+    slugger.reserve("content");
+    assert!(slugger.is_reserved("content"));
+    assert!(!slugger.is_reserved("main"));
+    assert_eq!(slugger.slug("Content"), "content-1");
+    slugger.reset();
+    assert_eq!(slugger.slug("Content"), "content-1");
+    assert_eq!(slugger.unique_path("content", &["main"]), "main-content");
+}
+
+#[test]
+fn test_max_length() {
+    let mut slugger = github_slugger::Slugger::default().max_length(Some(12));
+    assert_eq!(slugger.slug("Short"), "short");
+    assert_eq!(slugger.truncated("a-very-long-heading"), "a-very-long");
+    assert_eq!(slugger.slug("A very long heading"), "a-very-long");
+    assert_eq!(slugger.slug("A very long heading"), "a-very-1");
+    assert_eq!(slugger.slug("A very long"), "a-very-2");
+    assert_eq!(slugger.slug("Supercalifragilistic"), "supercalifra");
+    assert_eq!(slugger.slug("Supercalifragilistic"), "supercalif-1");
+    // truncated at a word boundary that falls exactly at the maximum length
+    assert_eq!(slugger.slug("Twelve chars plus"), "twelve-chars");
+}
+
+/// The previous (regex replacement based) implementation of `slug`.
+fn reference_slug(input: &str) -> String {
+    static REMOVE_PAT: &str = r"[\p{Other_Number}\p{Close_Punctuation}\p{Final_Punctuation}\p{Initial_Punctuation}\p{Open_Punctuation}\p{Other_Punctuation}\p{Dash_Punctuation}\p{Symbol}\p{Control}\p{Private_Use}\p{Format}\p{Unassigned}\p{Separator}]";
+    let s = input.to_lowercase();
+    let s = regex::Regex::new(REMOVE_PAT)
+        .unwrap()
+        .replace_all(&s, |caps: &regex::Captures| {
+            let c = caps.get(0).unwrap().as_str();
+            if c == " " || c == "-" {
+                "-".to_string()
+            } else if c.chars().all(|a| a.is_alphabetic()) {
+                c.to_string()
+            } else {
+                "".to_string()
+            }
+        });
+    s.replace(|c: char| c.is_whitespace(), "-")
+}
+
+#[test]
+fn test_slug_identical_to_reference() {
+    let mut inputs: Vec<String> = read_cases("tests/fixtures.json")
+        .into_iter()
+        .map(|case| case.input)
+        .collect();
+    inputs.extend(
+        [
+            "ΟΔΟΣ Σ ΣΑΣ",
+            "İstanbul",
+            "ǅemal",
+            "tab\tand\nnewline",
+            "non\u{a0}breaking\u{2003}space",
+            "ⓐⓑⓒ 🄰🄱🄲",
+            "e\u{301}",
+            "already-a-slug_1",
+            "",
+        ]
+        .map(String::from),
+    );
+    for input in &inputs {
+        let expected = reference_slug(input);
+        assert_eq!(github_slugger::slug(input), expected, "{:?}", input);
+        assert_eq!(github_slugger::slug_cow(input), expected, "{:?}", input);
+        let mut buffer = String::from("prefix:");
+        github_slugger::slug_into(input, &mut buffer);
+        assert_eq!(buffer, format!("prefix:{}", expected), "{:?}", input);
+    }
+    assert!(matches!(
+        github_slugger::slug_cow("already-a-slug_1"),
+        std::borrow::Cow::Borrowed(_)
+    ));
+}
@@ -0,0 +1,133 @@
+//! Parse a trailing attribute block in heading text,
+//! such as `# Heading {#custom-id .class key=val}`.
+//!
+//! Since the heading text may come from untrusted input,
+//! only the `key=val` attributes in [`ALLOWED_ATTRIBUTES`] are added to the heading;
+//! others (such as `style` or event handlers like `onclick`) are removed with the block, but ignored.
+
+use markdown_it::{parser::inline::Text, Node};
+
+/// The names of the `key=val` attributes that are added to the heading.
+pub const ALLOWED_ATTRIBUTES: &[&str] = &["lang", "dir", "title", "role", "translate"];
+
+#[derive(Debug, Default, PartialEq)]
+/// Attributes parsed from a heading's trailing `{...}` block.
+pub struct HeadingAttributes {
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    /// The allowed `key=val` attributes (see [`ALLOWED_ATTRIBUTES`]).
+    pub attrs: Vec<(&'static str, String)>,
+}
+
+impl HeadingAttributes {
+    /// Add the classes and other attributes (but not the id) to a node.
+    pub fn apply(&self, node: &mut Node) {
+        for class in &self.classes {
+            node.attrs.push(("class", class.clone()));
+        }
+        for (key, value) in &self.attrs {
+            node.attrs.push((key, value.clone()));
+        }
+    }
+}
+
+/// Remove a trailing attribute block from the heading's children,
+/// and return the parsed attributes.
+pub fn strip(heading: &mut Node) -> Option<HeadingAttributes> {
+    let last = heading.children.last_mut()?;
+    let text = last.cast_mut::<Text>()?;
+    let (start, attributes) = parse_trailing(&text.content)?;
+    text.content.truncate(start);
+    let trimmed_len = text.content.trim_end().len();
+    text.content.truncate(trimmed_len);
+    if text.content.is_empty() {
+        heading.children.pop();
+    }
+    Some(attributes)
+}
+
+/// Get the explicit id of a heading's trailing attribute block, without removing it.
+pub fn explicit_id(heading: &Node) -> Option<String> {
+    let text = heading.children.last()?.cast::<Text>()?;
+    parse_trailing(&text.content)?.1.id
+}
+
+/// Parse an attribute block at the end of the text,
+/// returning the byte index at which it starts, and the attributes.
+fn parse_trailing(text: &str) -> Option<(usize, HeadingAttributes)> {
+    let text = text.trim_end();
+    if !text.ends_with('}') {
+        return None;
+    }
+    let start = text.rfind('{')?;
+    let tokens = split_tokens(&text[start + 1..text.len() - 1])?;
+    if tokens.is_empty() {
+        return None;
+    }
+    let mut attributes = HeadingAttributes::default();
+    for token in tokens {
+        if let Some(id) = token.strip_prefix('#') {
+            if id.is_empty() {
+                return None;
+            }
+            attributes.id = Some(id.to_string());
+        } else if let Some(class) = token.strip_prefix('.') {
+            if class.is_empty() {
+                return None;
+            }
+            attributes.classes.push(class.to_string());
+        } else {
+            let (key, value) = token.split_once('=')?;
+            if key.is_empty()
+                || !key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+            {
+                return None;
+            }
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            match key {
+                "id" => attributes.id = Some(value.to_string()),
+                "class" => attributes
+                    .classes
+                    .extend(value.split_whitespace().map(String::from)),
+                _ => {
+                    if let Some(name) = ALLOWED_ATTRIBUTES.iter().find(|name| **name == key) {
+                        attributes.attrs.push((name, value.to_string()));
+                    }
+                }
+            }
+        }
+    }
+    Some((start, attributes))
+}
+
+/// Split the inside of an attribute block on whitespace,
+/// keeping double-quoted values together.
+fn split_tokens(inner: &str) -> Option<Vec<&str>> {
+    let mut tokens = vec![];
+    let mut token_start = None;
+    let mut in_quotes = false;
+    for (i, c) in inner.char_indices() {
+        if c == '"' {
+            in_quotes = !in_quotes;
+        }
+        if c.is_whitespace() && !in_quotes {
+            if let Some(start) = token_start.take() {
+                tokens.push(&inner[start..i]);
+            }
+        } else if token_start.is_none() {
+            token_start = Some(i);
+        }
+    }
+    if in_quotes {
+        return None;
+    }
+    if let Some(start) = token_start {
+        tokens.push(&inner[start..]);
+    }
+    Some(tokens)
+}
@@ -23,15 +23,32 @@
 //! );
 //! ```
 
+mod attributes;
+pub mod links;
+pub mod lint;
+pub mod numbering;
+pub mod outline;
+pub mod text;
+pub mod toc;
+
+use std::collections::HashMap;
+
 use github_slugger::Slugger;
+use lint::Linter;
 use markdown_it::{
-    parser::{core::CoreRule, extset::MarkdownItExt, inline::builtin::InlineParserRule},
+    parser::{
+        core::CoreRule,
+        extset::{MarkdownItExt, NodeExt},
+        inline::builtin::InlineParserRule,
+    },
     plugins::{
         cmark::block::{heading::ATXHeading, lheading::SetextHeader},
         html::html_inline::HtmlInline,
     },
-    MarkdownIt, Node, NodeValue,
+    MarkdownIt, Node, NodeValue, Renderer,
 };
+use numbering::{HeadingNumber, Numberer, NumberingOptions};
+use text::{heading_text, SlugTextOptions};
 
 /// Add the heading anchor plugin to MarkdownIt.
 pub fn add(md: &mut MarkdownIt) {
@@ -55,6 +72,69 @@ pub enum AnchorPosition {
     None,
 }
 
+/// A function to render a heading anchor.
+pub type RenderAnchor = fn(&HeadingAnchor, &Node, &mut dyn Renderer);
+
+#[derive(Debug)]
+/// How the anchor link is rendered.
+pub enum AnchorStyle {
+    /// A link hidden from screen readers (`aria-hidden`), inside the heading at `position`.
+    AriaHidden,
+    /// A link labelled by the heading (`aria-labelledby`), inside the heading at `position`.
+    /// The id is always added to the heading, so that it can be referenced.
+    ///
+    /// Unlike `LinkAfterHeader`, no visually hidden text is needed,
+    /// since screen readers announce the link with the text of the heading.
+    AriaLabelledBy,
+    /// Wrap the heading contents in the link (`position` and `inner_html` are ignored).
+    HeaderLink,
+    /// Place the link after the heading, labelled `Permalink: <text>`,
+    /// and wrap both in a `div` (as GitHub does; `position` is ignored).
+    LinkAfterHeader { wrapper_classes: Vec<String> },
+    /// Render the link with a custom function, inside the heading at `position`.
+    Custom(RenderAnchor),
+}
+
+/// A strategy for converting heading text to a slug.
+///
+/// Implementations need not ensure slugs are unique,
+/// since the plugin de-duplicates them per document.
+pub trait Slugify: std::fmt::Debug + Send + Sync {
+    /// Convert the (plain) text of a heading to a slug.
+    fn slugify(&self, text: &str) -> String;
+}
+
+#[derive(Debug, Default)]
+/// Generate slugs in the same way as GitHub (the default).
+pub struct GithubSlugify;
+impl Slugify for GithubSlugify {
+    fn slugify(&self, text: &str) -> String {
+        github_slugger::slug(text)
+    }
+}
+
+/// Generate slugs in the same way as another tool, such as GitLab or Pandoc.
+impl Slugify for github_slugger::Algorithm {
+    fn slugify(&self, text: &str) -> String {
+        self.slug(text)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// How duplicate slugs are made unique.
+pub enum Dedupe {
+    /// Append `-1`, `-2`, etc. in document order (as GitHub does).
+    #[default]
+    Sequential,
+    /// Prefix the slugs of the parent headings (nearest first, e.g. `usage-linux`),
+    /// falling back to a numeric suffix.
+    ///
+    /// The first heading with a given slug still takes it unprefixed, so adding a heading
+    /// with the same text earlier in the document changes the slug of the later one;
+    /// use `published_slugs` to keep published slugs stable.
+    ParentPath,
+}
+
 #[derive(Debug)]
 /// Options for the heading anchor plugin.
 pub struct HeadingAnchorOptions {
@@ -66,14 +146,40 @@ pub struct HeadingAnchorOptions {
     pub id_on_heading: bool,
     /// Where to add the anchor.
     pub position: AnchorPosition,
+    /// How the anchor is rendered.
+    pub style: AnchorStyle,
     /// Classes to add to the anchor.
     pub classes: Vec<String>,
     /// Inner HTML of the anchor.
     pub inner_html: String,
-    // TODO allow custom slugger
-    // (must make sure reset is called, or create new slugger for each use)
-    // TODO id prefix (different to href,
-    // see <https://github.com/Flet/markdown-it-github-headings/tree/master#why-should-i-prefix-heading-ids>)
+    /// Which heading content is included in the text used for the slug.
+    pub slug_text: SlugTextOptions,
+    /// Strategy for converting heading text to a slug.
+    pub slugify: Box<dyn Slugify>,
+    /// Ids used elsewhere in the page (such as `content` or `footer`),
+    /// which are never generated as slugs.
+    pub reserved_ids: Vec<String>,
+    /// The maximum length (in characters) of generated slugs,
+    /// which are truncated at a `-` where possible.
+    pub max_slug_length: Option<usize>,
+    /// Whether to transliterate the heading text to ASCII before generating the slug
+    /// (see [`github_slugger::transliterate`]).
+    pub ascii: bool,
+    /// Whether to parse a trailing `{#id .class key=val}` block in the heading text.
+    pub attributes: bool,
+    /// Whether (and how) to number the headings.
+    pub numbering: Option<NumberingOptions>,
+    /// How duplicate slugs are made unique.
+    pub dedupe: Dedupe,
+    /// Previously published slugs, keyed by heading path (see [`HeadingPath`]),
+    /// which are kept for the same headings and not assigned to any other heading.
+    pub published_slugs: HashMap<String, String>,
+    /// Whether to record diagnostics for the heading structure (see [`lint`]).
+    pub lint: bool,
+    /// Prefix to add to the slug in the id attribute.
+    pub id_prefix: String,
+    /// Prefix to add to the slug in the anchor href.
+    pub href_prefix: String,
 }
 impl Default for HeadingAnchorOptions {
     fn default() -> Self {
@@ -82,10 +188,23 @@ impl Default for HeadingAnchorOptions {
             max_level: 6,
             id_on_heading: false,
             position: AnchorPosition::Start,
+            style: AnchorStyle::AriaHidden,
             classes: vec![String::from("anchor")],
             inner_html: String::from(
                 r#"<svg class="octicon octicon-link" viewBox="0 0 16 16" version="1.1" width="16" height="16" aria-hidden="true"><path d="m7.775 3.275 1.25-1.25a3.5 3.5 0 1 1 4.95 4.95l-2.5 2.5a3.5 3.5 0 0 1-4.95 0 .751.751 0 0 1 .018-1.042.751.751 0 0 1 1.042-.018 1.998 1.998 0 0 0 2.83 0l2.5-2.5a2.002 2.002 0 0 0-2.83-2.83l-1.25 1.25a.751.751 0 0 1-1.042-.018.751.751 0 0 1-.018-1.042Zm-4.69 9.64a1.998 1.998 0 0 0 2.83 0l1.25-1.25a.751.751 0 0 1 1.042.018.751.751 0 0 1 .018 1.042l-1.25 1.25a3.5 3.5 0 1 1-4.95-4.95l2.5-2.5a3.5 3.5 0 0 1 4.95 0 .751.751 0 0 1-.018 1.042.751.751 0 0 1-1.042.018 1.998 1.998 0 0 0-2.83 0l-2.5 2.5a1.998 1.998 0 0 0 0 2.83Z"></path></svg>"#,
             ),
+            slug_text: SlugTextOptions::default(),
+            slugify: Box::new(GithubSlugify),
+            reserved_ids: vec![],
+            max_slug_length: None,
+            ascii: false,
+            attributes: false,
+            numbering: None,
+            dedupe: Dedupe::Sequential,
+            published_slugs: HashMap::new(),
+            lint: false,
+            id_prefix: String::new(),
+            href_prefix: String::new(),
         }
     }
 }
@@ -96,9 +215,17 @@ impl MarkdownItExt for HeadingAnchorOptions {}
 pub struct HeadingAnchor {
     pub href: String,
     pub id: Option<String>,
+    /// The plain text of the heading.
+    pub text: String,
+    /// Whether the link wraps the heading contents (`AnchorStyle::HeaderLink`).
+    pub wraps_content: bool,
+    pub custom: Option<RenderAnchor>,
 }
 impl NodeValue for HeadingAnchor {
-    fn render(&self, node: &Node, fmt: &mut dyn markdown_it::Renderer) {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        if let Some(custom) = self.custom {
+            return custom(self, node, fmt);
+        }
         let mut attrs = node.attrs.clone();
         if let Some(id) = &self.id {
             attrs.push(("id", id.clone()));
@@ -110,58 +237,265 @@ impl NodeValue for HeadingAnchor {
     }
 }
 
-struct AddHeadingAnchors;
+#[derive(Debug)]
+/// AST node wrapping a heading and the anchor link placed after it
+pub struct HeadingWrapper;
+impl NodeValue for HeadingWrapper {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.cr();
+        fmt.open("div", &node.attrs);
+        fmt.cr();
+        fmt.contents(&node.children);
+        fmt.cr();
+        fmt.close("div");
+        fmt.cr();
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The slug assigned to a heading, stored in the heading node's extensions.
+pub struct HeadingSlug(pub String);
+impl NodeExt for HeadingSlug {}
+
+#[derive(Debug, Clone)]
+/// The path of a heading, stored in the heading node's extensions:
+/// the (non de-duplicated) slugs of its parent headings and itself, joined by `/`,
+/// e.g. `install/linux`.
+///
+/// Used as the key of [`HeadingAnchorOptions::published_slugs`].
+pub struct HeadingPath(pub String);
+impl NodeExt for HeadingPath {}
+
+pub(crate) struct AddHeadingAnchors;
 impl CoreRule for AddHeadingAnchors {
     fn run(root: &mut Node, md: &MarkdownIt) {
         let options = md.ext.get::<HeadingAnchorOptions>().unwrap();
-        let mut slugger = Slugger::default();
+        let mut slugger = Slugger::default().max_length(options.max_slug_length);
+        for id in &options.reserved_ids {
+            slugger.reserve(id);
+        }
+        // explicit ids found anywhere in the document are registered up front,
+        // so that earlier generated slugs do not collide with them
+        root.walk(|node, _| {
+            for (name, value) in &node.attrs {
+                if *name == "id" {
+                    slugger.register(value);
+                }
+            }
+            if options.attributes && (node.is::<ATXHeading>() || node.is::<SetextHeader>()) {
+                if let Some(id) = attributes::explicit_id(node) {
+                    slugger.register(&id);
+                }
+            }
+        });
+        let mut numberer = Numberer::default();
+        let mut linter = options.lint.then(Linter::default);
+        let mut published = options.published_slugs.clone();
+        for slug in published.values() {
+            slugger.register(slug);
+        }
+        // the level and base slug of the current chain of parent headings
+        let mut parents: Vec<(u8, String)> = vec![];
         root.walk_mut(|node, _| {
             // TODO should be able to halt recursion for paragraphs etc,
             // that cannot contain headings
-            if let Some(value) = node.cast::<ATXHeading>() {
-                if value.level < options.min_level || value.level > options.max_level {
-                    return;
+            let level = if let Some(value) = node.cast::<ATXHeading>() {
+                value.level
+            } else if let Some(value) = node.cast::<SetextHeader>() {
+                value.level
+            } else {
+                return;
+            };
+            while parents.last().is_some_and(|(l, _)| *l >= level) {
+                parents.pop();
+            }
+            let attributes = if options.attributes {
+                attributes::strip(node)
+            } else {
+                None
+            };
+            if let Some(attributes) = &attributes {
+                attributes.apply(node);
+            }
+            if let Some(linter) = &mut linter {
+                linter.heading(level, node);
+            }
+            let explicit_id = attributes.and_then(|a| a.id);
+            if let (Some(linter), Some(id)) = (&mut linter, &explicit_id) {
+                linter.explicit_id(level, node, id);
+            }
+            let mut number = None;
+            if let Some(numbering) = &options.numbering {
+                number = numberer.next(level, numbering);
+                if let Some(number) = &number {
+                    let mut number_node = Node::new(HeadingNumber {
+                        number: number.clone(),
+                    });
+                    for class in &numbering.classes {
+                        number_node.attrs.push(("class", class.clone()));
+                    }
+                    node.children.insert(0, number_node);
                 }
             }
-            if let Some(value) = node.cast::<SetextHeader>() {
-                if value.level < options.min_level || value.level > options.max_level {
-                    return;
+            if level < options.min_level || level > options.max_level {
+                if let Some(id) = explicit_id {
+                    slugger.register(&id);
+                    node.attrs
+                        .push(("id", format!("{}{}", options.id_prefix, id)));
+                    node.ext.insert(HeadingPath(heading_path(&parents, &id)));
+                    node.ext.insert(HeadingSlug(id.clone()));
+                    parents.push((level, id));
                 }
+                return;
             }
-            if node.is::<ATXHeading>() || node.is::<SetextHeader>() {
-                // TODO strip image (alt) text
-                let id = slugger.slug(&node.collect_text());
-                if options.id_on_heading {
-                    node.attrs.push(("id", id.clone()));
+            let slug = match explicit_id {
+                Some(id) => {
+                    slugger.register(&id);
+                    node.ext.insert(HeadingPath(heading_path(&parents, &id)));
+                    parents.push((level, id.clone()));
+                    id
                 }
-                let anchor = HeadingAnchor {
-                    href: id.clone(),
-                    id: {
-                        if options.id_on_heading {
-                            None
-                        } else {
-                            Some(id)
+                None => {
+                    let mut text = heading_text(node, &options.slug_text);
+                    if let (Some(number), Some(numbering)) = (&number, &options.numbering) {
+                        if numbering.in_slug {
+                            text = format!("{} {}", number, text);
                         }
-                    },
-                };
-                let mut link_node = Node::new(anchor);
-                link_node.attrs.push(("aria-hidden", String::from("true")));
-                link_node.children.push(Node::new(HtmlInline {
-                    content: options.inner_html.clone(),
-                }));
-                for class in &options.classes {
-                    link_node.attrs.push(("class", class.clone()));
-                }
-                match options.position {
-                    AnchorPosition::Start => {
-                        node.children.insert(0, link_node);
                     }
-                    AnchorPosition::End => {
-                        node.children.push(link_node);
+                    if options.ascii {
+                        text = github_slugger::transliterate(&text);
                     }
-                    AnchorPosition::None => {}
+                    let base = options.slugify.slugify(&text);
+                    let path = heading_path(&parents, &base);
+                    let truncated = slugger.truncated(&base).to_string();
+                    let reserved = slugger.is_reserved(&truncated);
+                    let slug = match published.remove(&path) {
+                        Some(slug) => slug,
+                        None => match options.dedupe {
+                            Dedupe::Sequential => slugger.unique(&base),
+                            Dedupe::ParentPath => {
+                                let parent_slugs: Vec<&str> =
+                                    parents.iter().map(|(_, s)| s.as_str()).collect();
+                                slugger.unique_path(&base, &parent_slugs)
+                            }
+                        },
+                    };
+                    node.ext.insert(HeadingPath(path));
+                    parents.push((level, base.clone()));
+                    if let Some(linter) = &mut linter {
+                        linter.slug(level, node, &truncated, &slug, reserved);
+                    }
+                    slug
+                }
+            };
+            node.ext.insert(HeadingSlug(slug.clone()));
+            let id = format!("{}{}", options.id_prefix, slug);
+            let id_on_heading =
+                options.id_on_heading || matches!(options.style, AnchorStyle::AriaLabelledBy);
+            if id_on_heading {
+                node.attrs.push(("id", id.clone()));
+            }
+            // the other styles place the link regardless of `position`
+            let uses_position = !matches!(
+                options.style,
+                AnchorStyle::HeaderLink | AnchorStyle::LinkAfterHeader { .. }
+            );
+            if uses_position && matches!(options.position, AnchorPosition::None) {
+                return;
+            }
+            let text = heading_text(node, &SlugTextOptions::default());
+            let anchor = HeadingAnchor {
+                href: format!("{}{}", options.href_prefix, slug),
+                id: {
+                    if id_on_heading {
+                        None
+                    } else {
+                        Some(id.clone())
+                    }
+                },
+                text: text.clone(),
+                wraps_content: matches!(options.style, AnchorStyle::HeaderLink),
+                custom: match options.style {
+                    AnchorStyle::Custom(custom) => Some(custom),
+                    _ => None,
+                },
+            };
+            let mut link_node = Node::new(anchor);
+            match options.style {
+                AnchorStyle::AriaHidden => {
+                    link_node.attrs.push(("aria-hidden", String::from("true")));
+                }
+                AnchorStyle::AriaLabelledBy => {
+                    link_node.attrs.push(("aria-labelledby", id));
+                }
+                AnchorStyle::LinkAfterHeader { .. } => {
+                    link_node
+                        .attrs
+                        .push(("aria-label", format!("Permalink: {}", text)));
                 }
+                AnchorStyle::HeaderLink | AnchorStyle::Custom(_) => {}
+            }
+            for class in &options.classes {
+                link_node.attrs.push(("class", class.clone()));
+            }
+            if let AnchorStyle::HeaderLink = options.style {
+                link_node.children = std::mem::take(&mut node.children);
+                node.children.push(link_node);
+                return;
+            }
+            link_node.children.push(Node::new(HtmlInline {
+                content: options.inner_html.clone(),
+            }));
+            if let AnchorStyle::LinkAfterHeader { .. } = options.style {
+                // moved after the heading below, once all headings are processed
+                node.children.push(link_node);
+                return;
+            }
+            match options.position {
+                AnchorPosition::Start => {
+                    node.children.insert(0, link_node);
+                }
+                AnchorPosition::End => {
+                    node.children.push(link_node);
+                }
+                AnchorPosition::None => {}
             }
         });
+        if let AnchorStyle::LinkAfterHeader { wrapper_classes } = &options.style {
+            root.walk_mut(|node, _| {
+                for child in node.children.iter_mut() {
+                    if !(child.is::<ATXHeading>() || child.is::<SetextHeader>())
+                        || !child
+                            .children
+                            .last()
+                            .is_some_and(|c| c.is::<HeadingAnchor>())
+                    {
+                        continue;
+                    }
+                    let link_node = child.children.pop().unwrap();
+                    let srcmap = child.srcmap;
+                    let heading = std::mem::replace(child, Node::new(HeadingWrapper));
+                    child.srcmap = srcmap;
+                    for class in wrapper_classes {
+                        child.attrs.push(("class", class.clone()));
+                    }
+                    child.children = vec![heading, link_node];
+                }
+            });
+        }
+        if let Some(linter) = linter {
+            linter.finish(root);
+        }
+    }
+}
+
+/// Join the base slugs of the parent headings and a heading.
+fn heading_path(parents: &[(u8, String)], base: &str) -> String {
+    let mut path = String::new();
+    for (_, parent) in parents {
+        path.push_str(parent);
+        path.push('/');
     }
+    path.push_str(base);
+    path
 }
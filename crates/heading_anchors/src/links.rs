@@ -0,0 +1,206 @@
+//! Check internal links (`#fragment` or `other.md#fragment`)
+//! against the anchors of a set of parsed documents.
+//!
+//! The anchors of each document are the heading slugs (added by the heading anchor plugin),
+//! any other `id` attributes, and (with the `footnote` feature) footnote ids.
+//!
+//! ```rust
+//! use markdown_it_heading_anchors::links::{BrokenLinkKind, LinkChecker};
+//!
+//! let md = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(md);
+//! markdown_it_heading_anchors::add(md);
+//!
+//! let mut checker = LinkChecker::default();
+//! checker.add("index.md", &md.parse("# Index\n\n[ok](#index) [bad](docs/a.md#nope)"), md);
+//! checker.add("docs/a.md", &md.parse("# A\n\n[ok](../index.md#index)"), md);
+//!
+//! let broken = checker.check();
+//! assert_eq!(broken.len(), 1);
+//! assert_eq!(broken[0].source, "index.md");
+//! assert_eq!(broken[0].target, "docs/a.md");
+//! assert_eq!(broken[0].fragment, "nope");
+//! assert_eq!(broken[0].kind, BrokenLinkKind::MissingFragment);
+//! assert_eq!(broken[0].span, Some((22, 43)));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+use markdown_it::{plugins::cmark::inline::link::Link, MarkdownIt, Node};
+
+use crate::{HeadingAnchorOptions, HeadingSlug};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Why a link is broken.
+pub enum BrokenLinkKind {
+    /// The linked document is not in the document set.
+    MissingDocument,
+    /// The linked document has no anchor matching the fragment.
+    MissingFragment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A link to a fragment that does not exist.
+pub struct BrokenLink {
+    /// The path of the document containing the link.
+    pub source: String,
+    /// The URL of the link.
+    pub url: String,
+    /// The (resolved) path of the linked document.
+    pub target: String,
+    /// The (percent-decoded) fragment of the link.
+    pub fragment: String,
+    /// The byte offsets of the link in the source document.
+    pub span: Option<(usize, usize)>,
+    pub kind: BrokenLinkKind,
+}
+
+#[derive(Debug, Default)]
+struct Document {
+    anchors: HashSet<String>,
+    /// (url, span) of each link
+    links: Vec<(String, Option<(usize, usize)>)>,
+}
+
+#[derive(Debug, Default)]
+/// Collects the anchors and links of a set of documents, to check them against each other.
+pub struct LinkChecker {
+    documents: HashMap<String, Document>,
+}
+
+impl LinkChecker {
+    /// Add a parsed document, identified by its path (e.g. `docs/intro.md`).
+    ///
+    /// Relative link paths are resolved against the directory of this path.
+    pub fn add(&mut self, path: &str, root: &Node, md: &MarkdownIt) {
+        let href_prefix = md
+            .ext
+            .get::<HeadingAnchorOptions>()
+            .map(|o| o.href_prefix.as_str())
+            .unwrap_or_default();
+        let mut document = Document::default();
+        root.walk(|node, _| {
+            if let Some(HeadingSlug(slug)) = node.ext.get::<HeadingSlug>() {
+                document.anchors.insert(format!("{}{}", href_prefix, slug));
+            }
+            for (name, value) in &node.attrs {
+                if *name == "id" {
+                    document.anchors.insert(value.clone());
+                }
+            }
+            #[cfg(feature = "footnote")]
+            {
+                use markdown_it_footnote::{
+                    definitions::FootnoteDefinition, references::FootnoteReference,
+                };
+                if let Some(def) = node.cast::<FootnoteDefinition>() {
+                    if let Some(def_id) = def.def_id {
+                        document.anchors.insert(format!("fn{}", def_id));
+                    }
+                }
+                if let Some(reference) = node.cast::<FootnoteReference>() {
+                    document
+                        .anchors
+                        .insert(format!("fnref{}", reference.ref_id));
+                }
+            }
+            if let Some(link) = node.cast::<Link>() {
+                document
+                    .links
+                    .push((link.url.clone(), node.srcmap.map(|s| s.get_byte_offsets())));
+            }
+        });
+        self.documents.insert(normalize_path(path), document);
+    }
+
+    /// Check the fragment links of all documents, returning those that are broken.
+    pub fn check(&self) -> Vec<BrokenLink> {
+        let mut paths: Vec<&String> = self.documents.keys().collect();
+        paths.sort();
+        let mut broken = vec![];
+        for source in paths {
+            for (url, span) in &self.documents[source].links {
+                let Some((target, fragment)) = split_url(source, url) else {
+                    continue;
+                };
+                let kind = match self.documents.get(&target) {
+                    None => BrokenLinkKind::MissingDocument,
+                    Some(document) if !document.anchors.contains(&fragment) => {
+                        BrokenLinkKind::MissingFragment
+                    }
+                    Some(_) => continue,
+                };
+                broken.push(BrokenLink {
+                    source: source.clone(),
+                    url: url.clone(),
+                    target,
+                    fragment,
+                    span: *span,
+                    kind,
+                });
+            }
+        }
+        broken
+    }
+}
+
+/// Split an internal link URL into the resolved document path and the decoded fragment,
+/// or return None if it is not an internal link with a fragment.
+fn split_url(source: &str, url: &str) -> Option<(String, String)> {
+    let (path, fragment) = url.split_once('#')?;
+    if fragment.is_empty() {
+        return None;
+    }
+    // skip URLs with a scheme, such as `https://` or `mailto:`,
+    // and protocol-relative URLs, such as `//cdn.example.com`
+    let end = path.find(['/', '?']).unwrap_or(path.len());
+    if path[..end].contains(':') || path.starts_with("//") {
+        return None;
+    }
+    let path = path.split('?').next().unwrap_or_default();
+    let target = if path.is_empty() {
+        source.to_string()
+    } else if path.starts_with('/') {
+        normalize_path(path)
+    } else {
+        let dir = source.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+        normalize_path(&format!("{}/{}", dir, path))
+    };
+    Some((target, percent_decode(fragment)))
+}
+
+/// Normalize a path, by removing empty and `.` segments, and resolving `..` segments.
+fn normalize_path(path: &str) -> String {
+    let mut parts: Vec<&str> = vec![];
+    for part in path.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Decode `%XX` escapes (as added to non-ASCII link URLs by the parser).
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let high = (bytes[i + 1] as char).to_digit(16);
+            let low = (bytes[i + 2] as char).to_digit(16);
+            if let (Some(high), Some(low)) = (high, low) {
+                decoded.push((high * 16 + low) as u8);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
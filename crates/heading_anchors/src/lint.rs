@@ -0,0 +1,198 @@
+//! Check the structure of the headings in a document, for accessibility audits.
+//!
+//! With the `lint` option enabled, the heading anchor plugin records a [`HeadingDiagnostic`]
+//! for each problem it finds, which can be retrieved from the parsed document with [`diagnostics`].
+//!
+//! ```rust
+//! use markdown_it_heading_anchors::{
+//!     add_with_options, lint::{diagnostics, HeadingLintKind}, HeadingAnchorOptions,
+//! };
+//!
+//! let md = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(md);
+//! add_with_options(md, HeadingAnchorOptions {
+//!     lint: true,
+//!     ..Default::default()
+//! });
+//! let root = md.parse("# A\n### B\n# A");
+//!
+//! let kinds: Vec<_> = diagnostics(&root).iter().map(|d| d.kind.clone()).collect();
+//! assert_eq!(
+//!     kinds,
+//!     vec![
+//!         HeadingLintKind::SkippedLevel { from: 1, to: 3 },
+//!         HeadingLintKind::MultipleH1,
+//!         HeadingLintKind::DuplicateText { slug: String::from("a-1") },
+//!     ]
+//! );
+//! ```
+
+use std::{collections::HashSet, fmt};
+
+use markdown_it::{
+    parser::{core::Root, extset::RootExt},
+    Node,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The kind of problem found with a heading.
+pub enum HeadingLintKind {
+    /// The heading level is more than one below the previous heading (e.g. h1 → h3).
+    SkippedLevel { from: u8, to: u8 },
+    /// The document has more than one h1 heading (reported for each after the first).
+    MultipleH1,
+    /// The heading has no text content.
+    Empty,
+    /// The heading has text, but its slug is empty (e.g. it only contains emoji).
+    EmptySlug,
+    /// The heading has the same slug as a previous heading (or id), so a suffix was added.
+    ///
+    /// With `max_slug_length`, headings whose slugs are the same once truncated are also duplicates.
+    DuplicateText { slug: String },
+    /// The slug of the heading is one of the `reserved_ids`, so a suffix was added.
+    ReservedId { id: String, slug: String },
+    /// The heading has the same explicit `{#id}` as a previous heading
+    /// (explicit ids are used as written, so both headings have this id).
+    DuplicateId { id: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A problem found with a heading.
+pub struct HeadingDiagnostic {
+    pub kind: HeadingLintKind,
+    /// The heading level (1-6).
+    pub level: u8,
+    /// The plain text of the heading.
+    pub text: String,
+    /// The byte offsets of the heading in the source text.
+    pub span: Option<(usize, usize)>,
+}
+
+impl fmt::Display for HeadingDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            HeadingLintKind::SkippedLevel { from, to } => {
+                write!(f, "heading level skipped (h{} → h{})", from, to)
+            }
+            HeadingLintKind::MultipleH1 => write!(f, "multiple h1 headings"),
+            HeadingLintKind::Empty => write!(f, "empty heading"),
+            HeadingLintKind::EmptySlug => write!(f, "heading {:?} has an empty slug", self.text),
+            HeadingLintKind::DuplicateText { slug } => {
+                write!(f, "duplicate heading {:?} (slug {:?})", self.text, slug)
+            }
+            HeadingLintKind::ReservedId { id, slug } => write!(
+                f,
+                "heading {:?} has the reserved id {:?} (slug {:?})",
+                self.text, id, slug
+            ),
+            HeadingLintKind::DuplicateId { id } => {
+                write!(f, "heading {:?} has a duplicate id {:?}", self.text, id)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+/// The diagnostics of a document, stored in the root node's extensions.
+pub struct HeadingDiagnostics(pub Vec<HeadingDiagnostic>);
+impl RootExt for HeadingDiagnostics {}
+
+/// Get the heading diagnostics recorded for a parsed document
+/// (empty if the `lint` option is not enabled).
+pub fn diagnostics(root: &Node) -> &[HeadingDiagnostic] {
+    root.cast::<Root>()
+        .and_then(|r| r.ext.get::<HeadingDiagnostics>())
+        .map(|d| d.0.as_slice())
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Default)]
+/// Records diagnostics, as headings are visited in order.
+pub(crate) struct Linter {
+    previous_level: Option<u8>,
+    seen_h1: bool,
+    explicit_ids: HashSet<String>,
+    diagnostics: Vec<HeadingDiagnostic>,
+}
+impl Linter {
+    /// Check the level and content of a heading (called for every heading).
+    pub fn heading(&mut self, level: u8, heading: &Node) {
+        if let Some(previous) = self.previous_level {
+            if level > previous + 1 {
+                self.push(
+                    HeadingLintKind::SkippedLevel {
+                        from: previous,
+                        to: level,
+                    },
+                    level,
+                    heading,
+                );
+            }
+        }
+        self.previous_level = Some(level);
+        if level == 1 {
+            if self.seen_h1 {
+                self.push(HeadingLintKind::MultipleH1, level, heading);
+            }
+            self.seen_h1 = true;
+        }
+        if heading.collect_text().trim().is_empty() {
+            self.push(HeadingLintKind::Empty, level, heading);
+        }
+    }
+
+    /// Check the slug generated for a heading, before (`base`, truncated to the maximum length)
+    /// and after (`slug`) de-duplication.
+    pub fn slug(&mut self, level: u8, heading: &Node, base: &str, slug: &str, reserved: bool) {
+        if base.is_empty() {
+            // empty headings are already reported
+            if !heading.collect_text().trim().is_empty() {
+                self.push(HeadingLintKind::EmptySlug, level, heading);
+            }
+        } else if reserved {
+            self.push(
+                HeadingLintKind::ReservedId {
+                    id: base.to_string(),
+                    slug: slug.to_string(),
+                },
+                level,
+                heading,
+            );
+        } else if base != slug {
+            self.push(
+                HeadingLintKind::DuplicateText {
+                    slug: slug.to_string(),
+                },
+                level,
+                heading,
+            );
+        }
+    }
+
+    /// Check the explicit id of a heading, which is not de-duplicated.
+    pub fn explicit_id(&mut self, level: u8, heading: &Node, id: &str) {
+        if !self.explicit_ids.insert(id.to_string()) {
+            self.push(
+                HeadingLintKind::DuplicateId { id: id.to_string() },
+                level,
+                heading,
+            );
+        }
+    }
+
+    /// Store the diagnostics in the root node.
+    pub fn finish(self, root: &mut Node) {
+        if let Some(root) = root.cast_mut::<Root>() {
+            root.ext.insert(HeadingDiagnostics(self.diagnostics));
+        }
+    }
+
+    fn push(&mut self, kind: HeadingLintKind, level: u8, heading: &Node) {
+        self.diagnostics.push(HeadingDiagnostic {
+            kind,
+            level,
+            text: heading.collect_text().trim().to_string(),
+            span: heading.srcmap.map(|s| s.get_byte_offsets()),
+        });
+    }
+}
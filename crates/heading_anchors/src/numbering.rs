@@ -0,0 +1,109 @@
+//! Hierarchical numbering of headings, such as `1`, `1.2`, `1.2.3`.
+//!
+//! ```rust
+//! use markdown_it_heading_anchors::{
+//!     add_with_options, numbering::NumberingOptions, AnchorPosition, HeadingAnchorOptions,
+//! };
+//!
+//! let md = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(md);
+//! add_with_options(md, HeadingAnchorOptions {
+//!     position: AnchorPosition::None,
+//!     numbering: Some(NumberingOptions::default()),
+//!     ..Default::default()
+//! });
+//!
+//! assert_eq!(
+//!     md.parse("# A\n## B\n## C").render(),
+//!     "<h1><span class=\"heading-number\">1</span> A</h1>\n\
+//!     <h2><span class=\"heading-number\">1.1</span> B</h2>\n\
+//!     <h2><span class=\"heading-number\">1.2</span> C</h2>\n",
+//! );
+//! ```
+//!
+//! As in Pandoc, each heading level has its own counter, so skipped levels are numbered `0`
+//! (e.g. `1.0.1` for `### B` directly after `# A`, and `0.1` for a `##` before the first `#`).
+
+use markdown_it::{Node, NodeValue, Renderer};
+
+use crate::HeadingAnchor;
+
+#[derive(Debug)]
+/// Options for numbering headings.
+pub struct NumberingOptions {
+    /// The heading level that is numbered at the top of the hierarchy
+    /// (headings of a lower level are not numbered).
+    pub start_level: u8,
+    /// Separator between the numbers of each level.
+    pub separator: String,
+    /// Suffix to add after the number, e.g. `.` for `1.2.`.
+    pub suffix: String,
+    /// Whether the number is included in the text used for the slug.
+    pub in_slug: bool,
+    /// Classes to add to the number element.
+    pub classes: Vec<String>,
+}
+impl Default for NumberingOptions {
+    fn default() -> Self {
+        Self {
+            start_level: 1,
+            separator: String::from("."),
+            suffix: String::new(),
+            in_slug: false,
+            classes: vec![String::from("heading-number")],
+        }
+    }
+}
+
+#[derive(Debug)]
+/// AST node for a heading number, inserted as the first child of the heading
+pub struct HeadingNumber {
+    pub number: String,
+}
+impl NodeValue for HeadingNumber {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.open("span", &node.attrs);
+        fmt.text(&self.number);
+        fmt.close("span");
+        fmt.text(" ");
+    }
+}
+
+#[derive(Debug, Default)]
+/// Tracks the current section counters, as headings are visited in order.
+pub(crate) struct Numberer {
+    counters: Vec<usize>,
+}
+impl Numberer {
+    /// Return the next number for a heading of this level,
+    /// or None if the level is not numbered.
+    pub fn next(&mut self, level: u8, options: &NumberingOptions) -> Option<String> {
+        if level < options.start_level {
+            return None;
+        }
+        let depth = (level - options.start_level) as usize;
+        self.counters.resize(depth + 1, 0);
+        self.counters[depth] += 1;
+        let number = self
+            .counters
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(&options.separator);
+        Some(format!("{}{}", number, options.suffix))
+    }
+}
+
+/// Get the number of a heading node, if it has one.
+pub fn heading_number(heading: &Node) -> Option<&str> {
+    heading.children.iter().find_map(|child| {
+        if let Some(number) = child.cast::<HeadingNumber>() {
+            return Some(number.number.as_str());
+        }
+        // with `AnchorStyle::HeaderLink`, the number is inside the link
+        match child.cast::<HeadingAnchor>() {
+            Some(anchor) if anchor.wraps_content => heading_number(child),
+            _ => None,
+        }
+    })
+}
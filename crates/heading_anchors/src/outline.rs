@@ -0,0 +1,124 @@
+//! Extract the outline of a document,
+//! from the headings that have been assigned a slug by the heading anchor plugin.
+//!
+//! ```rust
+//! use markdown_it_heading_anchors::outline::{collect, OutlineItem};
+//!
+//! let md = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(md);
+//! markdown_it_heading_anchors::add(md);
+//! let root = md.parse("# A\n## B\n# C");
+//!
+//! let outline = collect(&root, 1, 6);
+//! assert_eq!(outline.len(), 2);
+//! assert_eq!(outline[0].slug, "a");
+//! assert_eq!(outline[0].children[0].text, "B");
+//! assert_eq!(outline[1].slug, "c");
+//! assert_eq!(outline[1].span, Some((9, 12)));
+//! ```
+//!
+//! With the `serde` feature enabled, the outline can be serialized,
+//! for example to build navigation for a static site.
+
+use std::collections::HashMap;
+
+use markdown_it::{
+    plugins::cmark::block::{heading::ATXHeading, lheading::SetextHeader},
+    Node,
+};
+
+use crate::{
+    numbering::heading_number,
+    text::{heading_text, SlugTextOptions},
+    HeadingPath, HeadingSlug,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// A heading in the document outline.
+pub struct OutlineItem {
+    /// The heading level (1-6).
+    pub level: u8,
+    /// The plain text of the heading (excluding image alt text and HTML tags).
+    pub text: String,
+    /// The number of the heading, if numbering is enabled.
+    pub number: Option<String>,
+    /// The slug of the heading (without any prefix).
+    pub slug: String,
+    /// The byte offsets of the heading in the source text.
+    pub span: Option<(usize, usize)>,
+    /// Sub-headings of this heading.
+    pub children: Vec<OutlineItem>,
+}
+
+/// Collect the outline of all headings between `min_level` and `max_level`,
+/// nesting each heading under the closest preceding heading of a lower level.
+///
+/// Only headings with a slug (added by the heading anchor plugin) are included.
+pub fn collect(root: &Node, min_level: u8, max_level: u8) -> Vec<OutlineItem> {
+    let mut items = vec![];
+    root.walk(|node, _| {
+        let level = if let Some(value) = node.cast::<ATXHeading>() {
+            value.level
+        } else if let Some(value) = node.cast::<SetextHeader>() {
+            value.level
+        } else {
+            return;
+        };
+        if level < min_level || level > max_level {
+            return;
+        }
+        if let Some(HeadingSlug(slug)) = node.ext.get::<HeadingSlug>() {
+            items.push(OutlineItem {
+                level,
+                text: heading_text(node, &SlugTextOptions::default()),
+                number: heading_number(node).map(String::from),
+                slug: slug.clone(),
+                span: node.srcmap.map(|s| s.get_byte_offsets()),
+                children: vec![],
+            });
+        }
+    });
+    nest(items)
+}
+
+/// Collect the slug of each heading, keyed by its [`HeadingPath`],
+/// e.g. to store when publishing, and pass as `published_slugs` to later builds.
+pub fn slugs_by_path(root: &Node) -> HashMap<String, String> {
+    let mut slugs = HashMap::new();
+    root.walk(|node, _| {
+        if let (Some(HeadingPath(path)), Some(HeadingSlug(slug))) =
+            (node.ext.get::<HeadingPath>(), node.ext.get::<HeadingSlug>())
+        {
+            slugs.entry(path.clone()).or_insert_with(|| slug.clone());
+        }
+    });
+    slugs
+}
+
+/// Convert a flat list of headings into a tree.
+fn nest(items: Vec<OutlineItem>) -> Vec<OutlineItem> {
+    let mut result = vec![];
+    // the current chain of open headings, with strictly increasing levels
+    let mut stack: Vec<OutlineItem> = vec![];
+    for item in items {
+        while stack.last().is_some_and(|last| last.level >= item.level) {
+            close(&mut stack, &mut result);
+        }
+        stack.push(item);
+    }
+    while !stack.is_empty() {
+        close(&mut stack, &mut result);
+    }
+    result
+}
+
+/// Pop the last open heading, and add it to its parent.
+fn close(stack: &mut Vec<OutlineItem>, result: &mut Vec<OutlineItem>) {
+    if let Some(item) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(item),
+            None => result.push(item),
+        }
+    }
+}
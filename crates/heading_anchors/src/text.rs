@@ -0,0 +1,81 @@
+//! Extract the text of a heading, from which its slug is generated.
+
+use markdown_it::{
+    parser::inline::{Text, TextSpecial},
+    plugins::{
+        cmark::inline::{
+            backticks::CodeInline,
+            image::Image,
+            newline::{Hardbreak, Softbreak},
+        },
+        html::html_inline::HtmlInline,
+    },
+    Node,
+};
+
+use crate::{numbering::HeadingNumber, HeadingAnchor};
+
+#[derive(Debug)]
+/// Options for which heading content is included in the text used for its slug.
+///
+/// The defaults match GitHub, which uses the text content of the rendered heading.
+pub struct SlugTextOptions {
+    /// Include the alt text of images.
+    pub image_alt: bool,
+    /// Include the raw content of inline HTML tags.
+    pub inline_html: bool,
+    /// Include the content of code spans.
+    pub code: bool,
+    /// Override the text of specific nodes, such as those added by other plugins
+    /// (return `None` to use the default handling).
+    pub custom: Option<fn(&Node) -> Option<String>>,
+}
+impl Default for SlugTextOptions {
+    fn default() -> Self {
+        Self {
+            image_alt: false,
+            inline_html: false,
+            code: true,
+            custom: None,
+        }
+    }
+}
+
+/// Get the text of a heading, from which its slug is generated.
+pub fn heading_text(heading: &Node, options: &SlugTextOptions) -> String {
+    let mut text = String::new();
+    collect(&heading.children, options, &mut text);
+    text
+}
+
+fn collect(nodes: &[Node], options: &SlugTextOptions, text: &mut String) {
+    for node in nodes {
+        if let Some(custom) = options.custom {
+            if let Some(content) = custom(node) {
+                text.push_str(&content);
+                continue;
+            }
+        }
+        if let Some(value) = node.cast::<Text>() {
+            text.push_str(&value.content);
+        } else if let Some(value) = node.cast::<TextSpecial>() {
+            text.push_str(&value.content);
+        } else if let Some(value) = node.cast::<HtmlInline>() {
+            if options.inline_html {
+                text.push_str(&value.content);
+            }
+        } else if node.is::<Softbreak>() || node.is::<Hardbreak>() {
+            text.push('\n');
+        } else if node
+            .cast::<HeadingAnchor>()
+            .is_some_and(|anchor| !anchor.wraps_content)
+            || node.is::<HeadingNumber>()
+            || (node.is::<Image>() && !options.image_alt)
+            || (node.is::<CodeInline>() && !options.code)
+        {
+            // skipped
+        } else {
+            collect(&node.children, options, text);
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! Replace a table of contents marker (e.g. `[[toc]]`)
+//! with a nested list of links to the document headings.
+//!
+//! This must be added after the heading anchor plugin,
+//! so that the links use the same slugs.
+//!
+//! ```rust
+//! let md = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(md);
+//! markdown_it_heading_anchors::add(md);
+//! markdown_it_heading_anchors::toc::add(md);
+//!
+//! let root = md.parse("[[toc]]\n\n# Heading");
+//! let mut names = vec![];
+//! root.walk(|node,_| { names.push(node.name()); });
+//! assert_eq!(names, vec![
+//! "markdown_it::parser::core::root::Root",
+//! "markdown_it_heading_anchors::toc::TableOfContents",
+//! "markdown_it::plugins::cmark::block::heading::ATXHeading",
+//! "markdown_it_heading_anchors::HeadingAnchor",
+//! "markdown_it::plugins::html::html_inline::HtmlInline",
+//! "markdown_it::parser::inline::builtin::skip_text::Text",
+//! ]);
+//! ```
+
+use markdown_it::{
+    parser::{
+        block::{BlockRule, BlockState},
+        core::CoreRule,
+        extset::MarkdownItExt,
+    },
+    plugins::cmark::block::paragraph::ParagraphScanner,
+    MarkdownIt, Node, NodeValue, Renderer,
+};
+
+use crate::{
+    outline::{collect, OutlineItem},
+    AddHeadingAnchors, HeadingAnchorOptions,
+};
+
+/// Add the table of contents plugin to MarkdownIt.
+pub fn add(md: &mut MarkdownIt) {
+    md.ext.get_or_insert_default::<TocOptions>();
+    add_rules(md);
+}
+
+/// Add the table of contents plugin to MarkdownIt, with options.
+pub fn add_with_options(md: &mut MarkdownIt, options: TocOptions) {
+    md.ext.insert(options);
+    add_rules(md);
+}
+
+fn add_rules(md: &mut MarkdownIt) {
+    md.block
+        .add_rule::<TocBlockScanner>()
+        .before::<ParagraphScanner>();
+    md.add_rule::<TocRule>().after::<AddHeadingAnchors>();
+}
+
+#[derive(Debug)]
+/// Options for the table of contents plugin.
+pub struct TocOptions {
+    /// Lines that are replaced with the table of contents.
+    pub markers: Vec<String>,
+    /// Minimum heading level to include.
+    pub min_level: u8,
+    /// Maximum heading level to include.
+    pub max_level: u8,
+    /// Classes to add to the `nav` container.
+    pub classes: Vec<String>,
+}
+impl Default for TocOptions {
+    fn default() -> Self {
+        Self {
+            markers: vec![String::from("[[toc]]"), String::from("[TOC]")],
+            min_level: 1,
+            max_level: 6,
+            classes: vec![String::from("table-of-contents")],
+        }
+    }
+}
+impl MarkdownItExt for TocOptions {}
+
+#[derive(Debug)]
+/// AST node for a table of contents
+pub struct TableOfContents {
+    pub items: Vec<OutlineItem>,
+    pub href_prefix: String,
+    pub number_attrs: Vec<(&'static str, String)>,
+}
+impl TableOfContents {
+    fn render_items(&self, items: &[OutlineItem], fmt: &mut dyn Renderer) {
+        fmt.cr();
+        fmt.open("ul", &[]);
+        fmt.cr();
+        for item in items {
+            fmt.open("li", &[]);
+            fmt.open(
+                "a",
+                &[("href", format!("#{}{}", self.href_prefix, item.slug))],
+            );
+            if let Some(number) = &item.number {
+                fmt.open("span", &self.number_attrs);
+                fmt.text(number);
+                fmt.close("span");
+                fmt.text(" ");
+            }
+            fmt.text(&item.text);
+            fmt.close("a");
+            if !item.children.is_empty() {
+                self.render_items(&item.children, fmt);
+            }
+            fmt.close("li");
+            fmt.cr();
+        }
+        fmt.close("ul");
+        fmt.cr();
+    }
+}
+impl NodeValue for TableOfContents {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        if self.items.is_empty() {
+            return;
+        }
+        fmt.cr();
+        fmt.open("nav", &node.attrs);
+        self.render_items(&self.items, fmt);
+        fmt.close("nav");
+        fmt.cr();
+    }
+}
+
+/// An extension for the block subparser.
+struct TocBlockScanner;
+impl BlockRule for TocBlockScanner {
+    fn run(state: &mut BlockState) -> Option<(Node, usize)> {
+        if state.line_indent(state.line) >= state.md.max_indent {
+            return None;
+        }
+        let options = state.md.ext.get::<TocOptions>()?;
+        let line = state.get_line(state.line).trim();
+        if !options.markers.iter().any(|marker| marker == line) {
+            return None;
+        }
+        let mut node = Node::new(TableOfContents {
+            items: vec![],
+            href_prefix: String::new(),
+            number_attrs: vec![],
+        });
+        for class in &options.classes {
+            node.attrs.push(("class", class.clone()));
+        }
+        Some((node, 1))
+    }
+}
+
+/// Populate the table of contents nodes, once all headings have slugs.
+struct TocRule;
+impl CoreRule for TocRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        let Some(options) = md.ext.get::<TocOptions>() else {
+            return;
+        };
+        let mut has_toc = false;
+        root.walk(|node, _| {
+            if node.is::<TableOfContents>() {
+                has_toc = true;
+            }
+        });
+        if !has_toc {
+            return;
+        }
+        let items = collect(root, options.min_level, options.max_level);
+        let anchor_options = md.ext.get::<HeadingAnchorOptions>();
+        let href_prefix = anchor_options
+            .map(|o| o.href_prefix.clone())
+            .unwrap_or_default();
+        let number_attrs: Vec<_> = anchor_options
+            .and_then(|o| o.numbering.as_ref())
+            .map(|n| n.classes.iter().map(|c| ("class", c.clone())).collect())
+            .unwrap_or_default();
+        root.walk_mut(|node, _| {
+            if let Some(toc) = node.cast_mut::<TableOfContents>() {
+                toc.items = items.clone();
+                toc.href_prefix = href_prefix.clone();
+                toc.number_attrs = number_attrs.clone();
+            }
+        });
+    }
+}
@@ -1,7 +1,11 @@
 use std::path::PathBuf;
 use testing::fixture;
 
-use markdown_it_heading_anchors::{add_with_options, AnchorPosition, HeadingAnchorOptions};
+use markdown_it_footnote::references::FootnoteReference;
+use markdown_it_heading_anchors::{
+    add_with_options, numbering::NumberingOptions, outline, AnchorPosition, AnchorStyle, Dedupe,
+    HeadingAnchor, HeadingAnchorOptions, Slugify,
+};
 
 #[fixture("tests/fixtures/*.md")]
 fn test_fixtures(file: PathBuf) {
@@ -17,3 +21,450 @@ fn test_fixtures(file: PathBuf) {
 
     dev::assert_no_diff(f, &actual);
 }
+
+#[fixture("tests/fixtures/attributes/*.md")]
+fn test_fixtures_attributes(file: PathBuf) {
+    let f = dev::read_fixture_file(file);
+
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        max_level: 3,
+        inner_html: String::from("¶"),
+        attributes: true,
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    let actual = parser.parse(&f.input).render();
+
+    dev::assert_no_diff(f, &actual);
+}
+
+#[fixture("tests/fixtures/toc/*.md")]
+fn test_fixtures_toc(file: PathBuf) {
+    let f = dev::read_fixture_file(file);
+
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    markdown_it_heading_anchors::toc::add(parser);
+    let actual = parser.parse(&f.input).render();
+
+    dev::assert_no_diff(f, &actual);
+}
+
+#[fixture("tests/fixtures/numbering/*.md")]
+fn test_fixtures_numbering(file: PathBuf) {
+    let f = dev::read_fixture_file(file);
+
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        numbering: Some(NumberingOptions::default()),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    markdown_it_heading_anchors::toc::add(parser);
+    let actual = parser.parse(&f.input).render();
+
+    dev::assert_no_diff(f, &actual);
+}
+
+#[fixture("tests/fixtures/slug_text/*.md")]
+fn test_fixtures_slug_text(file: PathBuf) {
+    let f = dev::read_fixture_file(file);
+
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    markdown_it::plugins::html::add(parser);
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    let actual = parser.parse(&f.input).render();
+
+    dev::assert_no_diff(f, &actual);
+}
+
+#[derive(Debug)]
+struct Underscores;
+impl Slugify for Underscores {
+    fn slugify(&self, text: &str) -> String {
+        text.to_lowercase().replace(' ', "_")
+    }
+}
+
+#[test]
+fn test_custom_slugify() {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        slugify: Box::new(Underscores),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    assert_eq!(
+        parser.parse("# My Heading\n# My Heading").render(),
+        "<h1><a aria-hidden=\"true\" class=\"anchor\" id=\"my_heading\" href=\"#my_heading\">¶</a>My Heading</h1>\n\
+        <h1><a aria-hidden=\"true\" class=\"anchor\" id=\"my_heading-1\" href=\"#my_heading-1\">¶</a>My Heading</h1>\n"
+    );
+}
+
+#[test]
+fn test_prefixes() {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        id_prefix: String::from("user-content-"),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    assert_eq!(
+        parser.parse("# Main").render(),
+        "<h1><a aria-hidden=\"true\" class=\"anchor\" id=\"user-content-main\" href=\"#main\">¶</a>Main</h1>\n"
+    );
+
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        id_on_heading: true,
+        id_prefix: String::from("h-"),
+        href_prefix: String::from("h-"),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    assert_eq!(
+        parser.parse("# Main").render(),
+        "<h1 id=\"h-main\"><a aria-hidden=\"true\" class=\"anchor\" href=\"#h-main\">¶</a>Main</h1>\n"
+    );
+}
+
+#[test]
+fn test_numbering_skipped_levels() {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        numbering: Some(NumberingOptions::default()),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    let root = parser.parse("## Preface\n# A\n### B\n## C\n#### D");
+    let mut numbers = vec![];
+    root.walk(|node, _| {
+        if let Some(number) = markdown_it_heading_anchors::numbering::heading_number(node) {
+            numbers.push(number.to_string());
+        }
+    });
+    assert_eq!(numbers, vec!["0.1", "1", "1.0.1", "1.1", "1.1.0.1"]);
+}
+
+#[test]
+fn test_numbering_in_slug() {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        numbering: Some(NumberingOptions {
+            start_level: 2,
+            suffix: String::from("."),
+            in_slug: true,
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    assert_eq!(
+        parser.parse("# Title\n## Intro\n## Usage").render(),
+        "<h1><a aria-hidden=\"true\" class=\"anchor\" id=\"title\" href=\"#title\">¶</a>Title</h1>\n\
+        <h2><a aria-hidden=\"true\" class=\"anchor\" id=\"1-intro\" href=\"#1-intro\">¶</a><span class=\"heading-number\">1.</span> Intro</h2>\n\
+        <h2><a aria-hidden=\"true\" class=\"anchor\" id=\"2-usage\" href=\"#2-usage\">¶</a><span class=\"heading-number\">2.</span> Usage</h2>\n"
+    );
+}
+
+#[test]
+fn test_slug_text_custom() {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    markdown_it_footnote::add(parser);
+    let mut options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        position: AnchorPosition::None,
+        id_on_heading: true,
+        ..Default::default()
+    };
+    options.slug_text.code = false;
+    options.slug_text.custom = Some(|node| {
+        node.cast::<FootnoteReference>()
+            .map(|r| r.def_id.to_string())
+    });
+    add_with_options(parser, options);
+    let html = parser.parse("# Heading[^a] `code`\n\n[^a]: note").render();
+    assert!(html.starts_with("<h1 id=\"heading1-\">"), "{}", html);
+}
+
+fn render_with_style(input: &str, options: HeadingAnchorOptions) -> String {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    add_with_options(parser, options);
+    parser.parse(input).render()
+}
+
+#[test]
+fn test_style_header_link() {
+    let options = HeadingAnchorOptions {
+        style: AnchorStyle::HeaderLink,
+        ..Default::default()
+    };
+    assert_eq!(
+        render_with_style("# Title *em*", options),
+        "<h1><a class=\"anchor\" id=\"title-em\" href=\"#title-em\">Title <em>em</em></a></h1>\n"
+    );
+}
+
+#[test]
+fn test_style_header_link_position_none() {
+    let options = HeadingAnchorOptions {
+        style: AnchorStyle::HeaderLink,
+        position: AnchorPosition::None,
+        ..Default::default()
+    };
+    assert_eq!(
+        render_with_style("# Title", options),
+        "<h1><a class=\"anchor\" id=\"title\" href=\"#title\">Title</a></h1>\n"
+    );
+}
+
+#[test]
+fn test_style_header_link_outline() {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        style: AnchorStyle::HeaderLink,
+        numbering: Some(NumberingOptions::default()),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+    markdown_it_heading_anchors::toc::add(parser);
+
+    let root = parser.parse("[[toc]]\n\n# Intro *em*");
+    let items = outline::collect(&root, 1, 6);
+    assert_eq!(items[0].text, "Intro em");
+    assert_eq!(items[0].number.as_deref(), Some("1"));
+    assert_eq!(
+        root.render(),
+        "<nav class=\"table-of-contents\">\n\
+        <ul>\n\
+        <li><a href=\"#intro-em\"><span class=\"heading-number\">1</span> Intro em</a></li>\n\
+        </ul>\n\
+        </nav>\n\
+        <h1><a class=\"anchor\" id=\"intro-em\" href=\"#intro-em\">\
+        <span class=\"heading-number\">1</span> Intro <em>em</em></a></h1>\n"
+    );
+}
+
+#[test]
+fn test_style_aria_labelledby() {
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        style: AnchorStyle::AriaLabelledBy,
+        ..Default::default()
+    };
+    assert_eq!(
+        render_with_style("# Title", options),
+        "<h1 id=\"title\"><a aria-labelledby=\"title\" class=\"anchor\" href=\"#title\">¶</a>Title</h1>\n"
+    );
+}
+
+#[test]
+fn test_style_link_after_header() {
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        id_prefix: String::from("user-content-"),
+        style: AnchorStyle::LinkAfterHeader {
+            wrapper_classes: vec![String::from("markdown-heading")],
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        render_with_style("# Title\n\ntext", options),
+        "<div class=\"markdown-heading\">\n\
+        <h1>Title</h1>\n\
+        <a aria-label=\"Permalink: Title\" class=\"anchor\" id=\"user-content-title\" href=\"#title\">¶</a>\n\
+        </div>\n\
+        <p>text</p>\n"
+    );
+}
+
+#[test]
+fn test_style_link_after_header_position_none() {
+    let options = HeadingAnchorOptions {
+        inner_html: String::from("¶"),
+        position: AnchorPosition::None,
+        style: AnchorStyle::LinkAfterHeader {
+            wrapper_classes: vec![],
+        },
+        ..Default::default()
+    };
+    assert_eq!(
+        render_with_style("# Title", options),
+        "<div>\n\
+        <h1>Title</h1>\n\
+        <a aria-label=\"Permalink: Title\" class=\"anchor\" id=\"title\" href=\"#title\">¶</a>\n\
+        </div>\n"
+    );
+}
+
+#[test]
+fn test_style_custom() {
+    fn render(anchor: &HeadingAnchor, _: &markdown_it::Node, fmt: &mut dyn markdown_it::Renderer) {
+        fmt.open("a", &[("href", format!("#{}", anchor.href))]);
+        fmt.text(&format!("Link to {}", anchor.text));
+        fmt.close("a");
+    }
+    let options = HeadingAnchorOptions {
+        position: AnchorPosition::End,
+        style: AnchorStyle::Custom(render),
+        ..Default::default()
+    };
+    assert_eq!(
+        render_with_style("# Title", options),
+        "<h1>Title<a href=\"#title\">Link to Title</a></h1>\n"
+    );
+}
+
+fn slugs(options: HeadingAnchorOptions, input: &str) -> Vec<String> {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    add_with_options(parser, options);
+    outline::collect(&parser.parse(input), 1, 6)
+        .iter()
+        .flat_map(|item| std::iter::once(item).chain(item.children.iter()))
+        .map(|item| item.slug.clone())
+        .collect()
+}
+
+#[test]
+fn test_dedupe_parent_path() {
+    let options = || HeadingAnchorOptions {
+        dedupe: Dedupe::ParentPath,
+        ..Default::default()
+    };
+    assert_eq!(
+        slugs(
+            options(),
+            "# Install\n## Linux\n# Usage\n## Linux\n## Linux"
+        ),
+        vec!["install", "linux", "usage", "usage-linux", "linux-1"]
+    );
+    // adding a heading to an earlier section does not change the later slugs
+    assert_eq!(
+        slugs(
+            options(),
+            "# Install\n## Linux\n## Linux\n# Usage\n## Linux"
+        ),
+        vec!["install", "linux", "install-linux", "usage", "usage-linux"]
+    );
+}
+
+#[test]
+fn test_published_slugs() {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    add_with_options(parser, HeadingAnchorOptions::default());
+    let published = outline::slugs_by_path(&parser.parse("# Install\n## Linux\n# Usage\n## Linux"));
+    assert_eq!(published["usage/linux"], "linux-1");
+
+    // a new section is inserted before the published headings
+    let options = HeadingAnchorOptions {
+        published_slugs: published,
+        ..Default::default()
+    };
+    assert_eq!(
+        slugs(
+            options,
+            "# Setup\n## Linux\n# Install\n## Linux\n# Usage\n## Linux"
+        ),
+        vec!["setup", "linux-2", "install", "linux", "usage", "linux-1"]
+    );
+}
+
+#[test]
+fn test_slug_algorithm() {
+    let options = HeadingAnchorOptions {
+        slugify: Box::new(github_slugger::Algorithm::Pandoc),
+        ..Default::default()
+    };
+    assert_eq!(
+        slugs(options, "# 1. Intro\n## 2024"),
+        vec!["intro", "section"]
+    );
+}
+
+#[test]
+fn test_ascii() {
+    let options = HeadingAnchorOptions {
+        ascii: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        slugs(options, "# Café Ñandú\n## Привет"),
+        vec!["cafe-nandu", "privet"]
+    );
+}
+
+#[test]
+fn test_reserved_ids_and_max_length() {
+    let options = HeadingAnchorOptions {
+        reserved_ids: vec![String::from("content")],
+        max_slug_length: Some(16),
+        ..Default::default()
+    };
+    assert_eq!(
+        slugs(options, "# Content\n## A rather long heading indeed"),
+        vec!["content-1", "a-rather-long"]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_outline_serde() {
+    let parser = &mut markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(parser);
+    let options = HeadingAnchorOptions {
+        numbering: Some(NumberingOptions::default()),
+        ..Default::default()
+    };
+    add_with_options(parser, options);
+
+    let items = outline::collect(&parser.parse("# A\n## B *b*"), 1, 6);
+    let json = serde_json::to_value(&items).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([{
+            "level": 1,
+            "text": "A",
+            "number": "1",
+            "slug": "a",
+            "span": [0, 3],
+            "children": [{
+                "level": 2,
+                "text": "B b",
+                "number": "1.1",
+                "slug": "b-b",
+                "span": [4, 12],
+                "children": [],
+            }],
+        }])
+    );
+    let items_back: Vec<outline::OutlineItem> = serde_json::from_value(json).unwrap();
+    assert_eq!(items_back, items);
+}
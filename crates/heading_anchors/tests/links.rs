@@ -0,0 +1,70 @@
+use markdown_it_heading_anchors::{
+    add_with_options,
+    links::{BrokenLinkKind, LinkChecker},
+    HeadingAnchorOptions,
+};
+
+fn parser() -> markdown_it::MarkdownIt {
+    let mut parser = markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(&mut parser);
+    let options = HeadingAnchorOptions {
+        id_prefix: String::from("user-content-"),
+        attributes: true,
+        ..Default::default()
+    };
+    add_with_options(&mut parser, options);
+    parser
+}
+
+#[test]
+fn test_same_document() {
+    let md = &parser();
+    let mut checker = LinkChecker::default();
+    checker.add(
+        "index.md",
+        &md.parse(
+            "# Привет\n\n## Usage {#use}\n\n\
+            [a](#привет) [b](#use) [c](#usage) [d](#) [e](https://example.com#usage) [f](//cdn.example.com/x#y)",
+        ),
+        md,
+    );
+    let broken = checker.check();
+    assert_eq!(broken.len(), 1, "{:?}", broken);
+    assert_eq!(broken[0].url, "#usage");
+    assert_eq!(broken[0].kind, BrokenLinkKind::MissingFragment);
+}
+
+#[test]
+fn test_other_documents() {
+    let md = &parser();
+    let mut checker = LinkChecker::default();
+    checker.add(
+        "./docs/guide/intro.md",
+        &md.parse("# Intro\n\n[a](../../index.md#home) [b](/docs/api.md#api) [c](missing.md#x)"),
+        md,
+    );
+    checker.add("index.md", &md.parse("# Home"), md);
+    checker.add("docs/api.md", &md.parse("# API"), md);
+    let broken = checker.check();
+    assert_eq!(broken.len(), 1, "{:?}", broken);
+    assert_eq!(broken[0].source, "docs/guide/intro.md");
+    assert_eq!(broken[0].target, "docs/guide/missing.md");
+    assert_eq!(broken[0].kind, BrokenLinkKind::MissingDocument);
+}
+
+#[cfg(feature = "footnote")]
+#[test]
+fn test_footnotes() {
+    let md = &mut parser();
+    markdown_it_footnote::add(md);
+    let mut checker = LinkChecker::default();
+    checker.add(
+        "index.md",
+        &md.parse("Text[^note]\n\n[^note]: A note\n\n[a](#fn1) [b](#fnref1) [c](#fn2)"),
+        md,
+    );
+    let broken = checker.check();
+    assert_eq!(broken.len(), 1, "{:?}", broken);
+    assert_eq!(broken[0].url, "#fn2");
+    assert_eq!(broken[0].kind, BrokenLinkKind::MissingFragment);
+}
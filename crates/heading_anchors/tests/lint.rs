@@ -0,0 +1,145 @@
+use markdown_it_heading_anchors::{
+    add_with_options,
+    lint::{diagnostics, HeadingLintKind},
+    HeadingAnchorOptions,
+};
+
+fn parser(options: HeadingAnchorOptions) -> markdown_it::MarkdownIt {
+    let mut parser = markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(&mut parser);
+    add_with_options(&mut parser, options);
+    parser
+}
+
+#[test]
+fn test_lint() {
+    let md = &parser(HeadingAnchorOptions {
+        lint: true,
+        ..Default::default()
+    });
+    let root = md.parse("# Title\n\n## 😄\n\n###\n\n## Usage\n\n## Usage\n\n# Other");
+    let diagnostics = diagnostics(&root);
+    let kinds: Vec<_> = diagnostics.iter().map(|d| &d.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            &HeadingLintKind::EmptySlug,
+            &HeadingLintKind::Empty,
+            &HeadingLintKind::DuplicateText {
+                slug: String::from("usage-1")
+            },
+            &HeadingLintKind::MultipleH1,
+        ]
+    );
+    assert_eq!(diagnostics[0].level, 2);
+    assert_eq!(diagnostics[0].text, "😄");
+    assert_eq!(diagnostics[0].span, Some((9, 16)));
+    assert_eq!(
+        diagnostics[2].to_string(),
+        "duplicate heading \"Usage\" (slug \"usage-1\")"
+    );
+}
+
+#[test]
+fn test_lint_levels() {
+    let md = &parser(HeadingAnchorOptions {
+        lint: true,
+        attributes: true,
+        max_level: 2,
+        ..Default::default()
+    });
+    // headings outside the anchor levels are still checked
+    let root = md.parse("# A {#a}\n#### B\n### C\n## D");
+    let kinds: Vec<_> = diagnostics(&root).iter().map(|d| &d.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![&HeadingLintKind::SkippedLevel { from: 1, to: 4 }]
+    );
+    assert_eq!(diagnostics(&root)[0].text, "B");
+}
+
+#[test]
+fn test_lint_disabled() {
+    let md = &parser(HeadingAnchorOptions::default());
+    let root = md.parse("# A\n### A\n# A");
+    assert!(diagnostics(&root).is_empty());
+}
+
+#[test]
+fn test_lint_reserved_id() {
+    let md = &parser(HeadingAnchorOptions {
+        lint: true,
+        reserved_ids: vec![String::from("content")],
+        ..Default::default()
+    });
+    let root = md.parse(
+        "# Content
+## Content",
+    );
+    let kinds: Vec<_> = diagnostics(&root).iter().map(|d| &d.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            &HeadingLintKind::ReservedId {
+                id: String::from("content"),
+                slug: String::from("content-1")
+            },
+            &HeadingLintKind::ReservedId {
+                id: String::from("content"),
+                slug: String::from("content-2")
+            },
+        ]
+    );
+    assert_eq!(
+        diagnostics(&root)[0].to_string(),
+        "heading \"Content\" has the reserved id \"content\" (slug \"content-1\")"
+    );
+}
+
+#[test]
+fn test_lint_truncated() {
+    let md = &parser(HeadingAnchorOptions {
+        lint: true,
+        max_slug_length: Some(10),
+        ..Default::default()
+    });
+    // truncation alone is not a problem, but truncated slugs can still be duplicates
+    let root = md.parse(
+        "## A rather long heading
+## A rather different heading",
+    );
+    let kinds: Vec<_> = diagnostics(&root).iter().map(|d| &d.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![&HeadingLintKind::DuplicateText {
+            slug: String::from("a-rather-1")
+        }]
+    );
+}
+
+#[test]
+fn test_lint_duplicate_id() {
+    let md = &parser(HeadingAnchorOptions {
+        lint: true,
+        attributes: true,
+        position: markdown_it_heading_anchors::AnchorPosition::None,
+        id_on_heading: true,
+        ..Default::default()
+    });
+    let root = md.parse("# A {#x}\n## B {#x}\n## C {#y}");
+    assert_eq!(
+        root.render(),
+        "<h1 id=\"x\">A</h1>\n<h2 id=\"x\">B</h2>\n<h2 id=\"y\">C</h2>\n"
+    );
+    let kinds: Vec<_> = diagnostics(&root).iter().map(|d| &d.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![&HeadingLintKind::DuplicateId {
+            id: String::from("x")
+        }]
+    );
+    assert_eq!(
+        diagnostics(&root)[0].to_string(),
+        "heading \"B\" has a duplicate id \"x\""
+    );
+}
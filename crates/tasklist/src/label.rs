@@ -0,0 +1,164 @@
+//! Associate the checkbox of each task with its text, using a `<label>`.
+//!
+//! Labels are only added when `TasklistOptions::label` is set.
+//! Each checkbox is given a unique `id` (from a counter, starting at 0 for each document),
+//! and the text is wrapped in a `<label for>` with that id.
+//! As for the `label` and `labelAfter` options of
+//! [markdown-it-task-lists](https://github.com/revin/markdown-it-task-lists),
+//! the label either wraps both the checkbox and the text, or only the text after the checkbox:
+//!
+//! ```rust
+//! use markdown_it_tasklist::{label::LabelOptions, TasklistOptions};
+//!
+//! let parser = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(parser);
+//! let mut options = TasklistOptions {
+//!     disabled: true,
+//!     label: Some(LabelOptions::default()),
+//!     ..Default::default()
+//! };
+//! markdown_it_tasklist::add_with_options(parser, options.clone());
+//! assert_eq!(
+//!     parser.parse("- [ ] task").render(),
+//!     "<ul class=\"contains-task-list\">\n\
+//!     <li class=\"task-list-item\"><label for=\"task-item-0\">\
+//!     <input id=\"task-item-0\" class=\"task-list-item-checkbox\" type=\"checkbox\" disabled=\"\"> task\
+//!     </label></li>\n\
+//!     </ul>\n",
+//! );
+//!
+//! options.label = Some(LabelOptions {
+//!     after: true,
+//!     ..Default::default()
+//! });
+//! parser.ext.insert(options);
+//! assert_eq!(
+//!     parser.parse("- [ ] task").render(),
+//!     "<ul class=\"contains-task-list\">\n\
+//!     <li class=\"task-list-item\">\
+//!     <input id=\"task-item-0\" class=\"task-list-item-checkbox\" type=\"checkbox\" disabled=\"\"> \
+//!     <label class=\"task-list-item-label\" for=\"task-item-0\">task</label></li>\n\
+//!     </ul>\n",
+//! );
+//! ```
+//!
+//! In loose lists, where the text is in a paragraph, the label is always placed after the checkbox.
+
+use markdown_it::{
+    common::sourcemap::SourcePos,
+    parser::inline::Text,
+    plugins::cmark::block::{
+        list::{BulletList, ListItem, OrderedList},
+        paragraph::Paragraph,
+    },
+    Node, NodeValue, Renderer,
+};
+
+use crate::TodoCheckbox;
+
+#[derive(Debug, Clone)]
+/// Options for adding labels to task checkboxes.
+pub struct LabelOptions {
+    /// Place the label after the checkbox, instead of wrapping it (`labelAfter`).
+    pub after: bool,
+    /// The prefix of the checkbox ids, which are followed by a per-document counter.
+    pub id_prefix: String,
+}
+
+impl Default for LabelOptions {
+    fn default() -> Self {
+        Self {
+            after: false,
+            id_prefix: String::from("task-item-"),
+        }
+    }
+}
+
+#[derive(Debug)]
+/// AST node for the label of a task.
+pub struct TaskLabel {
+    /// The id of the checkbox the label is for.
+    pub id: String,
+}
+
+impl NodeValue for TaskLabel {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let mut attrs = node.attrs.clone();
+        attrs.push(("for", self.id.clone()));
+        fmt.open("label", &attrs);
+        fmt.contents(&node.children);
+        fmt.close("label");
+    }
+}
+
+/// Add an id to each checkbox, and a label to its list item, in document order.
+pub(crate) fn add_labels(root: &mut Node, options: &LabelOptions) {
+    let mut counter = 0;
+    root.walk_mut(|item, _| {
+        if !item.is::<ListItem>()
+            || !item
+                .children
+                .first()
+                .is_some_and(|c| c.is::<TodoCheckbox>())
+        {
+            return;
+        }
+        let id = format!("{}{}", options.id_prefix, counter);
+        counter += 1;
+        item.children[0].attrs.push(("id", id.clone()));
+        let mut label = Node::new(TaskLabel { id });
+
+        if item.children.get(1).is_some_and(|c| c.is::<Paragraph>()) {
+            // loose list: label the text of the paragraph
+            label.attrs.push(("class", "task-list-item-label".into()));
+            let paragraph = &mut item.children[1];
+            label.children = std::mem::take(&mut paragraph.children);
+            paragraph.children = wrap_text(label);
+            return;
+        }
+        // tight list: label the inline content before any nested lists
+        let end = item
+            .children
+            .iter()
+            .position(|c| c.is::<BulletList>() || c.is::<OrderedList>())
+            .unwrap_or(item.children.len());
+        if options.after {
+            label.attrs.push(("class", "task-list-item-label".into()));
+            label.children = item.children.drain(1..end).collect();
+            let nodes = wrap_text(label);
+            item.children.splice(1..1, nodes);
+        } else {
+            label.children = item.children.drain(0..end).collect();
+            item.children.insert(0, label);
+        }
+    });
+}
+
+/// Move the leading whitespace of the label's text before it,
+/// returning the nodes to replace the label's content with.
+fn wrap_text(mut label: Node) -> Vec<Node> {
+    let mut nodes = vec![];
+    if let Some(first) = label.children.first_mut() {
+        let srcmap = first.srcmap;
+        if let Some(text) = first.cast_mut::<Text>() {
+            let trimmed = text.content.trim_start();
+            let len = text.content.len() - trimmed.len();
+            if len > 0 {
+                let mut space = Node::new(Text {
+                    content: text.content[..len].to_string(),
+                });
+                text.content.replace_range(..len, "");
+                if let Some(srcmap) = srcmap {
+                    let (start, end) = srcmap.get_byte_offsets();
+                    space.srcmap = Some(SourcePos::new(start, start + len));
+                    first.srcmap = Some(SourcePos::new(start + len, end));
+                }
+                nodes.push(space);
+            }
+        }
+    }
+    if !label.children.is_empty() {
+        nodes.push(label);
+    }
+    nodes
+}
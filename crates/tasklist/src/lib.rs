@@ -16,42 +16,226 @@
 //! ]);
 //! ```
 
+pub mod label;
+pub mod metadata;
+pub mod summary;
+
+use std::collections::HashMap;
+
 use markdown_it::{
+    common::sourcemap::{SourcePos, SourceWithLineStarts},
     parser::{
-        core::CoreRule,
+        core::{CoreRule, Root},
+        extset::MarkdownItExt,
         inline::{builtin::InlineParserRule, Text},
     },
-    plugins::cmark::block::{
-        list::{BulletList, ListItem, OrderedList},
-        paragraph::Paragraph,
+    plugins::cmark::{
+        block::{
+            list::{BulletList, ListItem, OrderedList},
+            paragraph::Paragraph,
+        },
+        inline::newline::{Hardbreak, Softbreak},
     },
     MarkdownIt, Node, NodeValue, Renderer,
 };
-use once_cell::sync::Lazy;
-use regex::Regex;
 
 /// Add the tasklist plugin to the parser
 pub fn add(md: &mut MarkdownIt) {
-    md.add_rule::<TasklistRule<false>>()
-        .after::<InlineParserRule>();
+    md.ext.get_or_insert_default::<TasklistOptions>();
+    md.add_rule::<TasklistRule>().after::<InlineParserRule>();
 }
 
-/// Add the tasklist plugin to the parser
+/// Add the tasklist plugin to the parser, with disabled checkboxes
 pub fn add_disabled(md: &mut MarkdownIt) {
-    md.add_rule::<TasklistRule<true>>()
-        .after::<InlineParserRule>();
+    md.ext.get_or_insert_default::<TasklistOptions>().disabled = true;
+    md.add_rule::<TasklistRule>().after::<InlineParserRule>();
+}
+
+/// Add the tasklist plugin to the parser, with options
+///
+/// ```rust
+/// use markdown_it_tasklist::{add_with_options, TasklistOptions};
+///
+/// let parser = &mut markdown_it::MarkdownIt::new();
+/// markdown_it::plugins::cmark::add(parser);
+/// add_with_options(parser, TasklistOptions {
+///     disabled: true,
+///     item_class: String::from("task"),
+///     list_class: String::new(),
+///     checkbox_class: String::from("checkbox"),
+///     ..Default::default()
+/// });
+/// assert_eq!(
+///     parser.parse("- [x] task").render(),
+///     "<ul>\n\
+///     <li class=\"task\"><input class=\"checkbox\" type=\"checkbox\" disabled=\"\" checked=\"\"> task</li>\n\
+///     </ul>\n",
+/// );
+/// ```
+pub fn add_with_options(md: &mut MarkdownIt, options: TasklistOptions) {
+    md.ext.insert(options);
+    md.add_rule::<TasklistRule>().after::<InlineParserRule>();
+}
+
+#[derive(Debug, Clone)]
+/// Options for the tasklist plugin.
+pub struct TasklistOptions {
+    /// Whether checkboxes are rendered as disabled
+    /// (otherwise they are rendered with `data-task-index` and `data-sourcepos` attributes).
+    pub disabled: bool,
+    /// The class of list items that are tasks (omitted if empty).
+    pub item_class: String,
+    /// The class of lists that contain tasks (omitted if empty).
+    pub list_class: String,
+    /// The class of the checkbox `<input>` (omitted if empty).
+    pub checkbox_class: String,
+    /// Whether tasks are recognised in lists nested within a list item
+    /// (otherwise only top-level lists contain tasks).
+    pub nested: bool,
+    /// Whether `[X]` is recognised as well as `[x]`, with the state of `x` in `states`.
+    pub uppercase: bool,
+    /// The characters recognised between the brackets of a task.
+    pub states: TaskStates,
+    /// Render a progress indicator on list items with nested tasks.
+    pub progress: Option<summary::ProgressOptions>,
+    /// Parse metadata tokens in the text of tasks.
+    pub metadata: Option<metadata::MetadataOptions>,
+    /// Associate each checkbox with the text of its task, using a `<label>`.
+    pub label: Option<label::LabelOptions>,
+}
+impl MarkdownItExt for TasklistOptions {}
+
+impl Default for TasklistOptions {
+    fn default() -> Self {
+        Self {
+            disabled: false,
+            item_class: String::from("task-list-item"),
+            list_class: String::from("contains-task-list"),
+            checkbox_class: String::from("task-list-item-checkbox"),
+            nested: true,
+            uppercase: true,
+            states: TaskStates::default(),
+            progress: None,
+            metadata: None,
+            label: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The state of a task.
+pub enum TaskState {
+    /// `[ ]`
+    Unchecked,
+    /// `[x]`
+    Checked,
+    /// `[-]` (with `TaskStates::extended`)
+    Cancelled,
+    /// `[/]` (with `TaskStates::extended`)
+    InProgress,
+    /// `[>]` (with `TaskStates::extended`)
+    Deferred,
+    /// `[?]` (with `TaskStates::extended`)
+    Question,
+}
+
+impl TaskState {
+    /// Whether the checkbox is rendered as checked (for completed and cancelled tasks).
+    pub fn is_checked(&self) -> bool {
+        matches!(self, TaskState::Checked | TaskState::Cancelled)
+    }
+
+    /// The name of the state, as rendered in the `data-task` attribute.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TaskState::Unchecked => "unchecked",
+            TaskState::Checked => "checked",
+            TaskState::Cancelled => "cancelled",
+            TaskState::InProgress => "in-progress",
+            TaskState::Deferred => "deferred",
+            TaskState::Question => "question",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+/// The characters recognised between the brackets of a task, and their states.
+///
+/// A whitespace character is looked up as `' '`, and `X` as `x` (see `TasklistOptions::uppercase`).
+/// The default only recognises `[ ]` and `[x]`, as in GFM.
+/// To change the states, set them in the `TasklistOptions`:
+///
+/// ```rust
+/// use markdown_it_tasklist::{TaskStates, TasklistOptions};
+///
+/// let parser = &mut markdown_it::MarkdownIt::new();
+/// markdown_it::plugins::cmark::add(parser);
+/// markdown_it_tasklist::add_with_options(parser, TasklistOptions {
+///     states: TaskStates::extended(),
+///     ..Default::default()
+/// });
+/// let html = parser.parse("- [/] task").render();
+/// assert!(html.contains("data-task=\"in-progress\""));
+/// ```
+pub struct TaskStates(pub HashMap<char, TaskState>);
+
+impl Default for TaskStates {
+    fn default() -> Self {
+        Self(HashMap::from([
+            (' ', TaskState::Unchecked),
+            ('x', TaskState::Checked),
+        ]))
+    }
+}
+
+impl TaskStates {
+    /// Whether only the GFM states (`Unchecked` and `Checked`) are recognised.
+    pub fn is_gfm(&self) -> bool {
+        self.0
+            .values()
+            .all(|state| matches!(state, TaskState::Unchecked | TaskState::Checked))
+    }
+
+    /// The GFM states, plus `[-]` cancelled, `[/]` in progress, `[>]` deferred
+    /// and `[?]` question (as used by Obsidian).
+    pub fn extended() -> Self {
+        let mut states = Self::default();
+        states.0.extend([
+            ('-', TaskState::Cancelled),
+            ('/', TaskState::InProgress),
+            ('>', TaskState::Deferred),
+            ('?', TaskState::Question),
+        ]);
+        states
+    }
 }
 
 #[derive(Debug)]
 pub struct TodoCheckbox {
+    /// Whether the checkbox is checked (derived from the state).
     pub checked: bool,
     pub disabled: bool,
+    pub state: TaskState,
+    /// The metadata tokens of the task (empty unless `TasklistOptions::metadata` is set).
+    pub metadata: metadata::TaskMetadata,
+    /// The class of the `<input>` (omitted if empty).
+    pub class: String,
+    /// Whether the state is rendered in a `data-task` attribute
+    /// (for all tasks, when `TasklistOptions::states` includes states other than GFM's).
+    pub data_task: bool,
 }
 
 impl NodeValue for TodoCheckbox {
     fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
         let mut attrs = node.attrs.clone();
-        attrs.push(("class", "task-list-item-checkbox".into()));
+        // the sourcepos plugin may also have added this attribute
+        let mut has_sourcepos = false;
+        attrs.retain(|(name, _)| {
+            *name != "data-sourcepos" || !std::mem::replace(&mut has_sourcepos, true)
+        });
+        if !self.class.is_empty() {
+            attrs.push(("class", self.class.clone()));
+        }
         attrs.push(("type", "checkbox".into()));
         if self.disabled {
             attrs.push(("disabled", "".into()));
@@ -59,82 +243,261 @@ impl NodeValue for TodoCheckbox {
         if self.checked {
             attrs.push(("checked", "".into()));
         }
+        if self.data_task {
+            attrs.push(("data-task", self.state.name().into()));
+        }
         fmt.self_close("input", &attrs);
     }
 }
 
-struct TasklistRule<const DISABLED: bool>;
+struct TasklistRule;
 
-static CHECKBOX_CHECKED_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^\[[xX]\][\s\t\n\v\f\r]").unwrap());
-static CHECKBOX_UNCHECKED_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"^\[\s\][\s\t\n\v\f\r]").unwrap());
-
-impl<const DISABLED: bool> CoreRule for TasklistRule<DISABLED> {
-    fn run(root: &mut Node, _: &MarkdownIt) {
-        fn walk_recursive(node: &mut Node, disabled: bool) {
+impl CoreRule for TasklistRule {
+    fn run(root: &mut Node, md: &MarkdownIt) {
+        fn walk_recursive(node: &mut Node, source: &str, options: &TasklistOptions, in_item: bool) {
             if node.is::<Paragraph>() {
                 // Paragraphs cannot contain lists, so we can stop here,
                 // without walking children
                 return;
             }
-            if node.is::<BulletList>() || node.is::<OrderedList>() {
+            if (node.is::<BulletList>() || node.is::<OrderedList>()) && (options.nested || !in_item)
+            {
                 let mut contains_task = false;
                 for item in node.children.iter_mut() {
                     if !item.is::<ListItem>() {
                         continue;
                     }
-                    if let Some(child) = item.children.first_mut() {
-                        // can be a paragraph->text or text, depending on if the list is tight
-                        let mut text_value = None;
-                        if child.cast_mut::<Paragraph>().is_some() {
-                            if let Some(child) = child.children.first_mut() {
-                                if let Some(value) = child.cast_mut::<Text>() {
-                                    text_value = Some(value);
-                                }
-                            }
-                        } else if let Some(value) = child.cast_mut::<Text>() {
-                            text_value = Some(value);
-                        }
-                        if let Some(text) = text_value {
-                            // TODO fix source mappings
-                            if CHECKBOX_UNCHECKED_RE.is_match(&text.content) {
-                                contains_task = true;
-                                text.content.replace_range(0..3, "");
-                                item.attrs.push(("class", "task-list-item".into()));
-                                item.children.insert(
-                                    0,
-                                    Node::new(TodoCheckbox {
-                                        checked: false,
-                                        disabled,
-                                    }),
-                                );
-                            } else if CHECKBOX_CHECKED_RE.is_match(&text.content) {
-                                contains_task = true;
-                                text.content.replace_range(0..3, "");
-                                item.attrs.push(("class", "task-list-item".into()));
-                                item.children.insert(
-                                    0,
-                                    Node::new(TodoCheckbox {
-                                        checked: true,
-                                        disabled,
-                                    }),
-                                );
-                            }
-                        }
+                    let Some((state, srcmap)) = strip_checkbox(item, source, options) else {
+                        continue;
+                    };
+                    contains_task = true;
+                    if !options.item_class.is_empty() {
+                        item.attrs.push(("class", options.item_class.clone()));
                     }
+                    let metadata = options
+                        .metadata
+                        .as_ref()
+                        .map(|metadata_options| metadata::extract(item, metadata_options))
+                        .unwrap_or_default();
+                    let mut checkbox = Node::new(TodoCheckbox {
+                        checked: state.is_checked(),
+                        disabled: options.disabled,
+                        state,
+                        metadata,
+                        class: options.checkbox_class.clone(),
+                        data_task: !options.states.is_gfm(),
+                    });
+                    checkbox.srcmap = srcmap;
+                    item.children.insert(0, checkbox);
                 }
-                if contains_task {
-                    node.attrs.push(("class", "contains-task-list".into()));
+                if contains_task && !options.list_class.is_empty() {
+                    node.attrs.push(("class", options.list_class.clone()));
                 }
             }
+            let in_item = in_item || node.is::<ListItem>();
             for n in node.children.iter_mut() {
                 stacker::maybe_grow(64 * 1024, 1024 * 1024, || {
-                    walk_recursive(n, disabled);
+                    walk_recursive(n, source, options, in_item);
                 });
             }
         }
 
-        walk_recursive(root, DISABLED);
+        let default_options = TasklistOptions::default();
+        let options = md.ext.get::<TasklistOptions>().unwrap_or(&default_options);
+        let source = root
+            .cast::<Root>()
+            .map(|r| r.content.clone())
+            .unwrap_or_default();
+        walk_recursive(root, &source, options, false);
+
+        if !options.disabled {
+            // number the (clickable) checkboxes in document order, for `toggle_task`
+            let mapping = SourceWithLineStarts::new(&source);
+            let mut index = 0;
+            root.walk_mut(|node, _| {
+                if !node.is::<TodoCheckbox>() {
+                    return;
+                }
+                node.attrs.push(("data-task-index", index.to_string()));
+                if let Some(srcmap) = node.srcmap {
+                    let ((startline, startcol), (endline, endcol)) = srcmap.get_positions(&mapping);
+                    node.attrs.push((
+                        "data-sourcepos",
+                        format!("{}:{}-{}:{}", startline, startcol, endline, endcol),
+                    ));
+                }
+                index += 1;
+            });
+        }
+
+        // labels are added first, so that they end before the progress indicators
+        if let Some(label_options) = &options.label {
+            label::add_labels(root, label_options);
+        }
+        if let Some(progress_options) = &options.progress {
+            summary::add_progress(root, progress_options);
+        }
+    }
+}
+
+/// Toggle the task with the given index (as rendered in the `data-task-index` attribute),
+/// replacing `[ ]` with `[x]`, or `[x]` with `[ ]`, in the markdown source.
+///
+/// The source is parsed with the given parser, which should be the one that rendered the index,
+/// so that the tasks are found with the same plugins and `TasklistOptions`.
+/// The source is returned unchanged if there is no task with this index.
+/// Checked tasks (see `TaskState::is_checked`) are replaced with `[ ]`, and others with `[x]`.
+///
+/// ```rust
+/// let parser = &mut markdown_it::MarkdownIt::new();
+/// markdown_it::plugins::cmark::add(parser);
+/// markdown_it_tasklist::add(parser);
+///
+/// let source = "- [ ] a\n- [x] b\n";
+/// assert_eq!(markdown_it_tasklist::toggle_task(parser, source, 0), "- [x] a\n- [x] b\n");
+/// assert_eq!(markdown_it_tasklist::toggle_task(parser, source, 1), "- [ ] a\n- [ ] b\n");
+/// assert_eq!(markdown_it_tasklist::toggle_task(parser, source, 2), source);
+/// ```
+pub fn toggle_task(md: &MarkdownIt, source: &str, index: usize) -> String {
+    let root = md.parse(source);
+    let mut checkboxes = vec![];
+    root.walk(|node, _| {
+        if let Some(checkbox) = node.cast::<TodoCheckbox>() {
+            checkboxes.push((checkbox.checked, node.srcmap));
+        }
+    });
+    let Some((checked, Some(srcmap))) = checkboxes.get(index).copied() else {
+        return source.to_string();
+    };
+    let (start, end) = srcmap.get_byte_offsets();
+    if !source
+        .get(start..end)
+        .is_some_and(|marker| marker.starts_with('[') && marker.ends_with(']'))
+    {
+        return source.to_string();
+    }
+    let replacement = if checked { "[ ]" } else { "[x]" };
+    format!("{}{}{}", &source[..start], replacement, &source[end..])
+}
+
+/// Remove a leading `[ ]` or `[x]` (or other state) from the inline content of a list item,
+/// returning its state, and its source position.
+///
+/// The checkbox is matched against the source text at the start of the content,
+/// so it is found even if the inline rules have split it from the following text,
+/// or parsed it as another node (e.g. a reference link).
+fn strip_checkbox(
+    item: &mut Node,
+    source: &str,
+    options: &TasklistOptions,
+) -> Option<(TaskState, Option<SourcePos>)> {
+    // the inline content is in a paragraph in loose lists,
+    // or directly in the list item in tight lists
+    let inlines = match item.children.first() {
+        Some(first) if first.is::<Paragraph>() => &mut item.children[0].children,
+        _ => &mut item.children,
+    };
+    let Some(srcmap) = inlines.first()?.srcmap else {
+        // without source positions, fall back to the text of the first node
+        let text = inlines.first_mut()?.cast_mut::<Text>()?;
+        let (state, len) = match_checkbox(&text.content, options)?;
+        text.content.replace_range(0..len, "");
+        return Some((state, None));
+    };
+    let start = srcmap.get_byte_offsets().0;
+    let (state, len) = match_checkbox(source.get(start..)?, options)?;
+    let end = start + len;
+
+    // the nodes starting within the checkbox must end within it,
+    // except for a text node that continues after it
+    let mut remove = 0;
+    let mut trim = None;
+    for node in inlines.iter() {
+        let Some((node_start, node_end)) = node.srcmap.map(|s| s.get_byte_offsets()) else {
+            break;
+        };
+        if node_start >= end {
+            break;
+        }
+        if node_end <= end {
+            remove += 1;
+            continue;
+        }
+        let text = node.cast::<Text>()?;
+        let prefix = source.get(node_start..end)?;
+        if !text.content.starts_with(prefix) {
+            return None;
+        }
+        trim = Some((prefix.len(), node_end));
+        break;
+    }
+    // the checkbox must be followed by more content on the same line, or a line break
+    if trim.is_none() {
+        let next = inlines.get(remove)?;
+        if !(next.is::<Softbreak>() || next.is::<Hardbreak>()) {
+            let next_start = next.srcmap?.get_byte_offsets().0;
+            if source.get(end..next_start)?.contains('\n') {
+                return None;
+            }
+        }
+    }
+
+    inlines.drain(..remove);
+    if let Some((len, node_end)) = trim {
+        let node = &mut inlines[0];
+        if let Some(text) = node.cast_mut::<Text>() {
+            text.content.replace_range(0..len, "");
+        }
+        node.srcmap = Some(SourcePos::new(end, node_end));
+    }
+    Some((state, Some(SourcePos::new(start, end))))
+}
+
+/// Match `[c]` followed by whitespace at the start of the text,
+/// returning the state, and the byte length of the checkbox.
+fn match_checkbox(text: &str, options: &TasklistOptions) -> Option<(TaskState, usize)> {
+    let mut chars = text.chars();
+    if chars.next()? != '[' {
+        return None;
+    }
+    let marker = chars.next()?;
+    if chars.next()? != ']' || !chars.next()?.is_whitespace() {
+        return None;
+    }
+    let key = match marker {
+        c if c.is_whitespace() => ' ',
+        'X' if options.uppercase => 'x',
+        'X' => return None,
+        c => c,
+    };
+    let state = *options.states.0.get(&key)?;
+    // the marker may be a multi-byte character
+    Some((state, marker.len_utf8() + 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("- [ ] task", &["Root:0:10", "BulletList:0:10", "ListItem:0:10", "TodoCheckbox:2:5", "Text:5:10"])]
+    #[case("- [x] task\n\n  more", &["Root:0:18", "BulletList:0:18", "ListItem:0:18", "TodoCheckbox:2:5", "Paragraph:2:10", "Text:5:10", "Paragraph:14:18", "Text:14:18"])]
+    #[case("1. [X] a", &["Root:0:8", "OrderedList:0:8", "ListItem:0:8", "TodoCheckbox:3:6", "Text:6:8"])]
+    #[case("- [\u{3000}] a", &["Root:0:9", "BulletList:0:9", "ListItem:0:9", "TodoCheckbox:2:7", "Text:7:9"])]
+    fn test_sourcepos(#[case] input: &str, #[case] expected: &[&str]) {
+        let md = &mut markdown_it::MarkdownIt::new();
+        markdown_it::plugins::cmark::add(md);
+        add(md);
+        let node = md.parse(input);
+        let mut pos = vec![];
+        node.walk(|n, _| {
+            if let Some(s) = n.srcmap {
+                let (i, j) = s.get_byte_offsets();
+                let name = n.name().split("::").last().unwrap_or_default();
+                pos.push(format!("{}:{}:{}", name, i, j));
+            }
+        });
+        assert_eq!(pos, expected);
     }
 }
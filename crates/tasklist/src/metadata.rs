@@ -0,0 +1,320 @@
+//! Parse inline metadata tokens of tasks, such as `@alice`, `due:2026-11-01` and `!high`.
+//!
+//! Metadata is only parsed when `TasklistOptions::metadata` is set,
+//! and is stored in the `metadata` field of each `TodoCheckbox`:
+//!
+//! ```rust
+//! use markdown_it_tasklist::{metadata::MetadataOptions, TasklistOptions, TodoCheckbox};
+//!
+//! let parser = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(parser);
+//! markdown_it_tasklist::add_with_options(parser, TasklistOptions {
+//!     disabled: true,
+//!     metadata: Some(MetadataOptions::default()),
+//!     ..Default::default()
+//! });
+//! let root = parser.parse("- [ ] Ship release @alice due:2026-11-01 !high");
+//!
+//! let mut metadata = None;
+//! root.walk(|node, _| {
+//!     if let Some(checkbox) = node.cast::<TodoCheckbox>() {
+//!         metadata = Some(checkbox.metadata.clone());
+//!     }
+//! });
+//! let metadata = metadata.unwrap();
+//! assert_eq!(metadata.assignees, vec!["alice"]);
+//! assert_eq!(metadata.due.as_deref(), Some("2026-11-01"));
+//! assert_eq!(metadata.priority.as_deref(), Some("high"));
+//! assert_eq!(
+//!     root.render(),
+//!     "<ul class=\"contains-task-list\">\n\
+//!     <li class=\"task-list-item\"><input class=\"task-list-item-checkbox\" type=\"checkbox\" disabled=\"\"> Ship release \
+//!     <span class=\"task-assignee\">@alice</span> \
+//!     <span class=\"task-due\">due:2026-11-01</span> \
+//!     <span class=\"task-priority\">!high</span></li>\n\
+//!     </ul>\n",
+//! );
+//! ```
+
+use markdown_it::{
+    common::sourcemap::SourcePos,
+    parser::inline::Text,
+    plugins::cmark::{
+        block::{
+            list::{BulletList, OrderedList},
+            paragraph::Paragraph,
+        },
+        inline::newline::{Hardbreak, Softbreak},
+    },
+    Node, NodeValue, Renderer,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// The metadata of a task.
+pub struct TaskMetadata {
+    /// The names of `@name` tokens (without the prefix).
+    pub assignees: Vec<String>,
+    /// The value of the last `due:` token (without the prefix).
+    pub due: Option<String>,
+    /// The value of the last `!` token (without the prefix).
+    pub priority: Option<String>,
+}
+
+impl TaskMetadata {
+    /// Whether no metadata tokens were found.
+    pub fn is_empty(&self) -> bool {
+        self.assignees.is_empty() && self.due.is_none() && self.priority.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// How metadata tokens are rendered.
+pub enum MetadataRender {
+    /// Wrap each token in a `<span>` with a class (`task-assignee`, `task-due` or `task-priority`).
+    #[default]
+    Style,
+    /// Remove the tokens (and the whitespace before them) from the rendered text.
+    Remove,
+}
+
+#[derive(Debug, Clone)]
+/// Options for parsing task metadata.
+///
+/// A token must be a whole whitespace-separated word of the task's text
+/// (outside of emphasis, links and code spans), consisting of a prefix followed by a value.
+/// Assignee and priority values may only contain alphanumeric characters, `-`, `_` and `/`;
+/// due values may be any non-empty word.
+pub struct MetadataOptions {
+    /// The prefix of assignee tokens, or `None` to not recognise them.
+    pub assignee: Option<String>,
+    /// The prefix of due date tokens, or `None` to not recognise them.
+    pub due: Option<String>,
+    /// The prefix of priority tokens, or `None` to not recognise them.
+    pub priority: Option<String>,
+    pub render: MetadataRender,
+}
+
+impl Default for MetadataOptions {
+    fn default() -> Self {
+        Self {
+            assignee: Some(String::from("@")),
+            due: Some(String::from("due:")),
+            priority: Some(String::from("!")),
+            render: MetadataRender::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The kind of a metadata token.
+pub enum MetadataKind {
+    Assignee,
+    Due,
+    Priority,
+}
+
+impl MetadataKind {
+    /// The class of the rendered `<span>`.
+    pub fn class(&self) -> &'static str {
+        match self {
+            MetadataKind::Assignee => "task-assignee",
+            MetadataKind::Due => "task-due",
+            MetadataKind::Priority => "task-priority",
+        }
+    }
+}
+
+#[derive(Debug)]
+/// AST node for a metadata token (with `MetadataRender::Style`).
+pub struct TaskMetadataToken {
+    pub kind: MetadataKind,
+    /// The value of the token (without the prefix).
+    pub value: String,
+    /// The text of the token, as written.
+    pub text: String,
+}
+
+impl NodeValue for TaskMetadataToken {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        let mut attrs = node.attrs.clone();
+        attrs.push(("class", self.kind.class().into()));
+        fmt.open("span", &attrs);
+        fmt.text(&self.text);
+        fmt.close("span");
+    }
+}
+
+impl MetadataOptions {
+    /// Match a whitespace-separated word against the token prefixes.
+    fn token(&self, word: &str) -> Option<(MetadataKind, String)> {
+        let is_name = |value: &str| {
+            value
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '/'))
+        };
+        let candidates = [
+            (MetadataKind::Due, &self.due),
+            (MetadataKind::Assignee, &self.assignee),
+            (MetadataKind::Priority, &self.priority),
+        ];
+        for (kind, prefix) in candidates {
+            let Some(value) = prefix
+                .as_deref()
+                .filter(|p| !p.is_empty())
+                .and_then(|p| word.strip_prefix(p))
+            else {
+                continue;
+            };
+            if value.is_empty() || (kind != MetadataKind::Due && !is_name(value)) {
+                continue;
+            }
+            return Some((kind, value.to_string()));
+        }
+        None
+    }
+}
+
+/// Parse (and style or remove) the metadata tokens in the text of a task list item,
+/// whose checkbox has already been stripped.
+pub(crate) fn extract(item: &mut Node, options: &MetadataOptions) -> TaskMetadata {
+    let mut metadata = TaskMetadata::default();
+    // the inline content is in a paragraph in loose lists,
+    // or directly in the list item (before any nested lists) in tight lists
+    let inlines = match item.children.first() {
+        Some(first) if first.is::<Paragraph>() => &mut item.children[0].children,
+        _ => &mut item.children,
+    };
+    let mut end = inlines
+        .iter()
+        .position(|n| n.is::<BulletList>() || n.is::<OrderedList>())
+        .unwrap_or(inlines.len());
+    let mut i = 0;
+    while i < end {
+        if !inlines[i].is::<Text>() {
+            i += 1;
+            continue;
+        }
+        // a word at the edge of a text node only counts if it is next to a line break
+        let is_break = |n: &Node| n.is::<Softbreak>() || n.is::<Hardbreak>();
+        let start_boundary = i == 0 || is_break(&inlines[i - 1]);
+        let end_boundary = i + 1 == end || is_break(&inlines[i + 1]);
+        let replacement = split(
+            &inlines[i],
+            options,
+            start_boundary,
+            end_boundary,
+            &mut metadata,
+        );
+        let Some(nodes) = replacement else {
+            i += 1;
+            continue;
+        };
+        let count = nodes.len();
+        inlines.splice(i..i + 1, nodes);
+        i += count;
+        end = end + count - 1;
+    }
+    if options.render == MetadataRender::Remove {
+        // remove whitespace left at the end of the text
+        if let Some(text) = inlines[..end].last_mut().and_then(|n| n.cast_mut::<Text>()) {
+            text.content.truncate(text.content.trim_end().len());
+        }
+    }
+    metadata
+}
+
+/// Find the metadata tokens in a text node, and return the nodes to replace it with
+/// (or `None` if it contains no tokens).
+fn split(
+    node: &Node,
+    options: &MetadataOptions,
+    start_boundary: bool,
+    end_boundary: bool,
+    metadata: &mut TaskMetadata,
+) -> Option<Vec<Node>> {
+    let content = node.cast::<Text>()?.content.as_str();
+    // positions can only be mapped if the text is unchanged from the source
+    let offset = node.srcmap.and_then(|s| {
+        let (start, end) = s.get_byte_offsets();
+        (end - start == content.len()).then_some(start)
+    });
+
+    let mut nodes = vec![];
+    // the start of the text not yet added to `nodes` (with `MetadataRender::Style`)
+    let mut pending = 0;
+    // the text kept (with `MetadataRender::Remove`), and the end of the previous word
+    let mut kept = String::new();
+    let mut previous = 0;
+    let mut found = false;
+    for word in content.split_whitespace() {
+        let start = word.as_ptr() as usize - content.as_ptr() as usize;
+        let end = start + word.len();
+        let whitespace = &content[previous..start];
+        previous = end;
+        let token = if (start == 0 && !start_boundary) || (end == content.len() && !end_boundary) {
+            None
+        } else {
+            options.token(word)
+        };
+        let Some((kind, value)) = token else {
+            kept.push_str(whitespace);
+            kept.push_str(word);
+            continue;
+        };
+        found = true;
+        match kind {
+            MetadataKind::Assignee => metadata.assignees.push(value.clone()),
+            MetadataKind::Due => metadata.due = Some(value.clone()),
+            MetadataKind::Priority => metadata.priority = Some(value.clone()),
+        }
+        match options.render {
+            MetadataRender::Style => {
+                push_text(&mut nodes, &content[pending..start], pending, offset);
+                let mut token = Node::new(TaskMetadataToken {
+                    kind,
+                    value,
+                    text: word.to_string(),
+                });
+                token.srcmap = offset.map(|o| SourcePos::new(o + start, o + end));
+                nodes.push(token);
+                pending = end;
+            }
+            MetadataRender::Remove => {
+                // remove the whitespace before the token,
+                // or after it if it is the first word of the text
+                if kept.trim().is_empty() {
+                    kept.push_str(whitespace);
+                    previous = content[end..]
+                        .find(|c: char| !c.is_whitespace())
+                        .map_or(content.len(), |i| end + i);
+                }
+            }
+        }
+    }
+    if !found {
+        return None;
+    }
+    match options.render {
+        MetadataRender::Style => {
+            push_text(&mut nodes, &content[pending..], pending, offset);
+        }
+        MetadataRender::Remove => {
+            kept.push_str(&content[previous..]);
+            let mut text = Node::new(Text { content: kept });
+            text.srcmap = node.srcmap;
+            nodes.push(text);
+        }
+    }
+    Some(nodes)
+}
+
+fn push_text(nodes: &mut Vec<Node>, content: &str, start: usize, offset: Option<usize>) {
+    if content.is_empty() {
+        return;
+    }
+    let mut text = Node::new(Text {
+        content: content.to_string(),
+    });
+    text.srcmap = offset.map(|o| SourcePos::new(o + start, o + start + content.len()));
+    nodes.push(text);
+}
@@ -0,0 +1,288 @@
+//! Extract the tasks of a parsed document, and summarise their progress.
+//!
+//! ```rust
+//! use markdown_it_tasklist::{summary, TaskState};
+//!
+//! let parser = &mut markdown_it::MarkdownIt::new();
+//! markdown_it::plugins::cmark::add(parser);
+//! markdown_it_tasklist::add(parser);
+//! let root = parser.parse("# Release\n\n- [x] build\n- [ ] publish\n  - [x] crates.io\n  - [ ] docs");
+//!
+//! let tasks = summary::collect(&root);
+//! assert_eq!(tasks.len(), 2);
+//! assert_eq!(tasks[1].text, "publish");
+//! assert_eq!(tasks[1].state, TaskState::Unchecked);
+//! assert_eq!(tasks[1].line, Some(4));
+//! assert_eq!(tasks[1].heading.as_deref(), Some("Release"));
+//! assert_eq!(tasks[1].subtasks[0].text, "crates.io");
+//!
+//! let lists = summary::list_progress(&root);
+//! assert_eq!((lists[0].done, lists[0].total), (1, 2));
+//! assert_eq!((lists[1].done, lists[1].total), (1, 2));
+//! ```
+
+use markdown_it::{
+    common::sourcemap::SourceWithLineStarts,
+    parser::{core::Root, inline::Text},
+    plugins::cmark::{
+        block::{
+            heading::ATXHeading,
+            lheading::SetextHeader,
+            list::{BulletList, ListItem, OrderedList},
+            paragraph::Paragraph,
+        },
+        inline::newline::{Hardbreak, Softbreak},
+    },
+    Node, NodeValue, Renderer,
+};
+
+use crate::{label::TaskLabel, metadata::TaskMetadata, TaskState, TodoCheckbox};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A task, and its subtasks.
+pub struct Task {
+    /// The plain text of the task (excluding subtasks).
+    pub text: String,
+    pub state: TaskState,
+    /// The index of the task in the document (as used by `toggle_task`).
+    pub index: usize,
+    /// The line of the task in the source text (starting from 1).
+    pub line: Option<usize>,
+    /// The text of the closest preceding heading.
+    pub heading: Option<String>,
+    /// The metadata tokens of the task (see `TasklistOptions::metadata`).
+    pub metadata: TaskMetadata,
+    /// Tasks in lists nested within this task.
+    pub subtasks: Vec<Task>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The completion counts of the tasks in a list.
+pub struct ListProgress {
+    /// The line of the list in the source text (starting from 1).
+    pub line: Option<usize>,
+    /// The number of completed tasks (see `TaskState::is_checked`).
+    pub done: usize,
+    /// The number of tasks (excluding those in nested lists).
+    pub total: usize,
+}
+
+/// Collect the tasks of a document as a tree,
+/// where tasks in lists nested within a (non-task) list item are included at the same level.
+pub fn collect(root: &Node) -> Vec<Task> {
+    let mut collector = Collector {
+        mapping: mapping(root),
+        index: 0,
+        heading: None,
+    };
+    collector.collect(&root.children)
+}
+
+/// Get the completion counts of each list containing tasks, in document order.
+pub fn list_progress(root: &Node) -> Vec<ListProgress> {
+    let mapping = mapping(root);
+    let mut lists = vec![];
+    root.walk(|node, _| {
+        if !(node.is::<BulletList>() || node.is::<OrderedList>()) {
+            return;
+        }
+        let (done, total) = count(node);
+        if total > 0 {
+            lists.push(ListProgress {
+                line: line(node, &mapping),
+                done,
+                total,
+            });
+        }
+    });
+    lists
+}
+
+struct Collector {
+    mapping: SourceWithLineStarts,
+    index: usize,
+    heading: Option<String>,
+}
+
+impl Collector {
+    fn collect(&mut self, nodes: &[Node]) -> Vec<Task> {
+        let mut tasks = vec![];
+        for node in nodes {
+            if node.is::<ATXHeading>() || node.is::<SetextHeader>() {
+                self.heading = Some(node.collect_text());
+            } else if let Some((checkbox_node, checkbox)) = task_checkbox(node) {
+                let mut task = Task {
+                    text: item_text(node),
+                    state: checkbox.state,
+                    index: self.index,
+                    line: line(checkbox_node, &self.mapping),
+                    heading: self.heading.clone(),
+                    metadata: checkbox.metadata.clone(),
+                    subtasks: vec![],
+                };
+                self.index += 1;
+                task.subtasks = self.collect(&node.children);
+                tasks.push(task);
+            } else if !node.is::<Paragraph>() {
+                tasks.extend(self.collect(&node.children));
+            }
+        }
+        tasks
+    }
+}
+
+/// Get the checkbox of a list item, if it is a task.
+fn task_checkbox(node: &Node) -> Option<(&Node, &TodoCheckbox)> {
+    if !node.is::<ListItem>() {
+        return None;
+    }
+    let mut first = node.children.first()?;
+    if first.is::<TaskLabel>() {
+        // the label may wrap the checkbox
+        first = first.children.first()?;
+    }
+    Some((first, first.cast::<TodoCheckbox>()?))
+}
+
+/// Get the text of a list item, excluding nested lists,
+/// with line breaks and paragraphs separated by newlines.
+fn item_text(item: &Node) -> String {
+    let mut text = String::new();
+    for child in &item.children {
+        if child.is::<BulletList>() || child.is::<OrderedList>() {
+            continue;
+        }
+        if child.is::<Paragraph>() && !text.trim().is_empty() {
+            text.push('\n');
+        }
+        child.walk(|node, _| {
+            if let Some(t) = node.cast::<Text>() {
+                text.push_str(&t.content);
+            } else if node.is::<Softbreak>() || node.is::<Hardbreak>() {
+                text.push('\n');
+            }
+        });
+    }
+    text.trim().to_string()
+}
+
+/// Count the (done, total) tasks that are direct items of a list.
+fn count(list: &Node) -> (usize, usize) {
+    let mut done = 0;
+    let mut total = 0;
+    for item in &list.children {
+        if let Some((_, checkbox)) = task_checkbox(item) {
+            total += 1;
+            if checkbox.state.is_checked() {
+                done += 1;
+            }
+        }
+    }
+    (done, total)
+}
+
+fn mapping(root: &Node) -> SourceWithLineStarts {
+    SourceWithLineStarts::new(
+        root.cast::<Root>()
+            .map(|r| r.content.as_str())
+            .unwrap_or_default(),
+    )
+}
+
+fn line(node: &Node, mapping: &SourceWithLineStarts) -> Option<usize> {
+    node.srcmap.map(|s| s.get_positions(mapping).0 .0 as usize)
+}
+
+#[derive(Debug, Clone)]
+/// Options for rendering a progress indicator (such as `3/5`) on list items
+/// whose nested lists contain tasks.
+///
+/// Set `TasklistOptions::progress` to enable the indicator:
+///
+/// ```rust
+/// use markdown_it_tasklist::{summary::ProgressOptions, TasklistOptions};
+///
+/// let parser = &mut markdown_it::MarkdownIt::new();
+/// markdown_it::plugins::cmark::add(parser);
+/// markdown_it_tasklist::add_with_options(parser, TasklistOptions {
+///     disabled: true,
+///     progress: Some(ProgressOptions::default()),
+///     ..Default::default()
+/// });
+/// assert_eq!(
+///     parser.parse("- [ ] release\n  - [x] build\n  - [ ] publish").render(),
+///     "<ul class=\"contains-task-list\">\n\
+///     <li class=\"task-list-item\"><input class=\"task-list-item-checkbox\" type=\"checkbox\" disabled=\"\"> release \
+///     <span class=\"task-progress\">1/2</span>\n\
+///     <ul class=\"contains-task-list\">\n\
+///     <li class=\"task-list-item\"><input class=\"task-list-item-checkbox\" type=\"checkbox\" disabled=\"\" checked=\"\"> build</li>\n\
+///     <li class=\"task-list-item\"><input class=\"task-list-item-checkbox\" type=\"checkbox\" disabled=\"\"> publish</li>\n\
+///     </ul>\n\
+///     </li>\n\
+///     </ul>\n",
+/// );
+/// ```
+pub struct ProgressOptions {
+    /// Classes to add to the indicator element.
+    pub classes: Vec<String>,
+}
+
+impl Default for ProgressOptions {
+    fn default() -> Self {
+        Self {
+            classes: vec![String::from("task-progress")],
+        }
+    }
+}
+
+#[derive(Debug)]
+/// AST node for the progress of the tasks in a list item's nested lists.
+pub struct TaskProgress {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl NodeValue for TaskProgress {
+    fn render(&self, node: &Node, fmt: &mut dyn Renderer) {
+        fmt.text(" ");
+        fmt.open("span", &node.attrs);
+        fmt.text(&format!("{}/{}", self.done, self.total));
+        fmt.close("span");
+    }
+}
+
+/// Add a `TaskProgress` node to each list item whose nested lists contain tasks,
+/// at the end of its first paragraph, or before the first nested list.
+pub(crate) fn add_progress(root: &mut Node, options: &ProgressOptions) {
+    root.walk_mut(|node, _| {
+        if !node.is::<ListItem>() {
+            return;
+        }
+        let mut done = 0;
+        let mut total = 0;
+        let mut first_list = None;
+        for (i, child) in node.children.iter().enumerate() {
+            if child.is::<BulletList>() || child.is::<OrderedList>() {
+                let (d, t) = count(child);
+                done += d;
+                total += t;
+                first_list.get_or_insert(i);
+            }
+        }
+        let Some(first_list) = first_list.filter(|_| total > 0) else {
+            return;
+        };
+        let mut progress = Node::new(TaskProgress { done, total });
+        for class in &options.classes {
+            progress.attrs.push(("class", class.clone()));
+        }
+        match node
+            .children
+            .iter_mut()
+            .find(|child| child.is::<Paragraph>())
+        {
+            Some(paragraph) => paragraph.children.push(progress),
+            None => node.children.insert(first_list, progress),
+        }
+    });
+}
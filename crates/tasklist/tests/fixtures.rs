@@ -1,3 +1,7 @@
+use markdown_it_tasklist::{
+    label::LabelOptions, metadata::MetadataOptions, summary::ProgressOptions, TaskStates,
+    TasklistOptions,
+};
 use std::path::PathBuf;
 use testing::fixture;
 
@@ -5,13 +9,30 @@ use testing::fixture;
 fn test_html(file: PathBuf) {
     let f = dev::read_fixture_file(file);
 
+    let mut options = TasklistOptions {
+        disabled: f.title.contains("DISABLED"),
+        nested: !f.title.contains("TOP-LEVEL"),
+        uppercase: !f.title.contains("LOWERCASE"),
+        ..Default::default()
+    };
+    if f.title.contains("EXTENDED") {
+        options.states = TaskStates::extended();
+    }
+    if f.title.contains("PROGRESS") {
+        options.progress = Some(ProgressOptions::default());
+    }
+    if f.title.contains("METADATA") {
+        options.metadata = Some(MetadataOptions::default());
+    }
+    if f.title.contains("LABEL") {
+        options.label = Some(LabelOptions {
+            after: f.title.contains("AFTER"),
+            ..Default::default()
+        });
+    }
     let parser = &mut markdown_it::MarkdownIt::new();
     markdown_it::plugins::cmark::add(parser);
-    if f.title.contains("DISABLED") {
-        markdown_it_tasklist::add_disabled(parser);
-    } else {
-        markdown_it_tasklist::add(parser);
-    }
+    markdown_it_tasklist::add_with_options(parser, options);
     let actual = parser.parse(&f.input).render();
 
     dev::assert_no_diff(f, &actual);
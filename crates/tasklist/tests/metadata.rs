@@ -0,0 +1,76 @@
+use markdown_it_tasklist::{
+    metadata::{MetadataOptions, MetadataRender, TaskMetadata},
+    summary::collect,
+    TasklistOptions,
+};
+
+fn parser(options: MetadataOptions) -> markdown_it::MarkdownIt {
+    let mut parser = markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(&mut parser);
+    markdown_it_tasklist::add_with_options(
+        &mut parser,
+        TasklistOptions {
+            disabled: true,
+            metadata: Some(options),
+            ..Default::default()
+        },
+    );
+    parser
+}
+
+#[test]
+fn test_remove() {
+    let md = &parser(MetadataOptions {
+        render: MetadataRender::Remove,
+        ..Default::default()
+    });
+    let root =
+        md.parse("- [ ] @alice Ship release due:2026-11-01 !high\n- [ ] line one @bob\n  line two");
+    assert_eq!(
+        root.render(),
+        "<ul class=\"contains-task-list\">\n\
+        <li class=\"task-list-item\"><input class=\"task-list-item-checkbox\" type=\"checkbox\" disabled=\"\"> Ship release</li>\n\
+        <li class=\"task-list-item\"><input class=\"task-list-item-checkbox\" type=\"checkbox\" disabled=\"\"> line one\nline two</li>\n\
+        </ul>\n"
+    );
+    let tasks = collect(&root);
+    assert_eq!(
+        tasks[0].metadata,
+        TaskMetadata {
+            assignees: vec![String::from("alice")],
+            due: Some(String::from("2026-11-01")),
+            priority: Some(String::from("high")),
+        }
+    );
+    assert_eq!(tasks[1].metadata.assignees, vec!["bob"]);
+    assert_eq!(tasks[1].text, "line one\nline two");
+}
+
+#[test]
+fn test_custom_syntax() {
+    let md = &parser(MetadataOptions {
+        assignee: Some(String::from("owner:")),
+        due: Some(String::from("📅")),
+        priority: None,
+        ..Default::default()
+    });
+    let tasks = collect(&md.parse("- [ ] fix owner:carol 📅friday !high @dave"));
+    assert_eq!(
+        tasks[0].metadata,
+        TaskMetadata {
+            assignees: vec![String::from("carol")],
+            due: Some(String::from("friday")),
+            priority: None,
+        }
+    );
+}
+
+#[test]
+fn test_not_enabled() {
+    let mut md = markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(&mut md);
+    markdown_it_tasklist::add(&mut md);
+    let tasks = collect(&md.parse("- [ ] fix @alice"));
+    assert!(tasks[0].metadata.is_empty());
+    assert_eq!(tasks[0].text, "fix @alice");
+}
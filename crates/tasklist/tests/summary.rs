@@ -0,0 +1,89 @@
+use markdown_it_tasklist::{
+    summary::{collect, list_progress, ListProgress},
+    TaskState, TaskStates, TasklistOptions,
+};
+
+fn parser() -> markdown_it::MarkdownIt {
+    let mut parser = markdown_it::MarkdownIt::new();
+    markdown_it::plugins::cmark::add(&mut parser);
+    markdown_it_tasklist::add_with_options(
+        &mut parser,
+        TasklistOptions {
+            states: TaskStates::extended(),
+            ..Default::default()
+        },
+    );
+    parser
+}
+
+#[test]
+fn test_collect() {
+    let source = "\
+# Plan
+
+1. Design
+   - [x] sketch *ideas*
+   - [-] prototype
+
+## Build
+
+- [/] backend
+
+  notes
+
+  - [ ] api
+- [ ] frontend";
+    let tasks = collect(&parser().parse(source));
+
+    let summary: Vec<_> = tasks
+        .iter()
+        .map(|t| {
+            (
+                t.text.as_str(),
+                t.state,
+                t.index,
+                t.line,
+                t.heading.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("sketch ideas", TaskState::Checked, 0, Some(4), Some("Plan")),
+            ("prototype", TaskState::Cancelled, 1, Some(5), Some("Plan")),
+            (
+                "backend\nnotes",
+                TaskState::InProgress,
+                2,
+                Some(9),
+                Some("Build")
+            ),
+            ("frontend", TaskState::Unchecked, 4, Some(14), Some("Build")),
+        ]
+    );
+    assert_eq!(tasks[2].subtasks.len(), 1);
+    assert_eq!(tasks[2].subtasks[0].text, "api");
+    assert_eq!(tasks[2].subtasks[0].index, 3);
+    assert_eq!(tasks[2].subtasks[0].line, Some(13));
+}
+
+#[test]
+fn test_list_progress() {
+    let source = "- [x] a\n- [-] b\n- [ ] c\n  - [ ] d\n- e\n\n* no tasks";
+    assert_eq!(
+        list_progress(&parser().parse(source)),
+        vec![
+            ListProgress {
+                line: Some(1),
+                done: 2,
+                total: 3
+            },
+            ListProgress {
+                line: Some(4),
+                done: 0,
+                total: 1
+            },
+        ]
+    );
+}
@@ -0,0 +1,88 @@
+use markdown_it::MarkdownIt;
+use markdown_it_tasklist::toggle_task;
+
+fn parser() -> MarkdownIt {
+    let mut parser = MarkdownIt::new();
+    markdown_it::plugins::cmark::add(&mut parser);
+    markdown_it_tasklist::add(&mut parser);
+    parser
+}
+
+#[test]
+fn test_toggle_task() {
+    let md = &parser();
+    let source =
+        "# Tasks\n\n1. foo\n   - [ ] nested\n   - [X] nested *checked*\n\n- [ ]\tloose\n\n  more\n";
+    assert_eq!(
+        toggle_task(md, source, 0),
+        source.replacen("- [ ] nested", "- [x] nested", 1)
+    );
+    assert_eq!(toggle_task(md, source, 1), source.replacen("[X]", "[ ]", 1));
+    assert_eq!(
+        toggle_task(md, source, 2),
+        source.replacen("[ ]\t", "[x]\t", 1)
+    );
+    assert_eq!(toggle_task(md, source, 3), source);
+    // toggling twice returns the original source
+    assert_eq!(toggle_task(md, &toggle_task(md, source, 0), 0), source);
+}
+
+#[test]
+fn test_toggle_task_index_matches_render() {
+    let md = &parser();
+    let source = "- [ ] a\n  - [ ] b\n- [ ] c";
+    let html = md.parse(source).render();
+    assert!(
+        html.contains("data-task-index=\"1\" data-sourcepos=\"2:5-2:7\""),
+        "{}",
+        html
+    );
+    assert_eq!(toggle_task(md, source, 1), "- [ ] a\n  - [x] b\n- [ ] c");
+}
+
+#[test]
+fn test_toggle_task_uses_parser_plugins() {
+    let md = &mut parser();
+    markdown_it_front_matter::add(md);
+    // the list in the front matter is not a task
+    let source = "---\n- [ ] yaml\n---\n- [ ] real";
+    assert!(md
+        .parse(source)
+        .render()
+        .contains("data-task-index=\"0\" data-sourcepos=\"4:3-4:5\""));
+    assert_eq!(
+        toggle_task(md, source, 0),
+        "---\n- [ ] yaml\n---\n- [x] real"
+    );
+    assert_eq!(toggle_task(md, source, 1), source);
+}
+
+#[test]
+fn test_toggle_task_with_options() {
+    use markdown_it_tasklist::{TaskStates, TasklistOptions};
+
+    let md = &mut MarkdownIt::new();
+    markdown_it::plugins::cmark::add(md);
+    markdown_it_tasklist::add_with_options(
+        md,
+        TasklistOptions {
+            nested: false,
+            uppercase: false,
+            states: TaskStates::extended(),
+            ..Default::default()
+        },
+    );
+    let source = "- [X] a\n  - [ ] b\n- [/] c\n- [-] d";
+    // not tasks by default
+    assert_eq!(toggle_task(&parser(), source, 2), source);
+    // `[X]` and the nested list are not tasks with these options
+    assert_eq!(
+        toggle_task(md, source, 0),
+        "- [X] a\n  - [ ] b\n- [x] c\n- [-] d"
+    );
+    assert_eq!(
+        toggle_task(md, source, 1),
+        "- [X] a\n  - [ ] b\n- [/] c\n- [ ] d"
+    );
+    assert_eq!(toggle_task(md, source, 2), source);
+}